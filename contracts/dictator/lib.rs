@@ -1,18 +1,74 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
 
 pub use self::dictator::{Dictator, DictatorRef};
 
+use game_theory::logics::traits::types::{
+    ActionRatios, ConfigError, GameConfigs, GameError, NonRevealPolicy, PayoffFormula, TiePolicy,
+};
+
+/// The configurations used by the `default` constructor: 2 players in a single round.
+pub fn default_configs() -> GameConfigs {
+    GameConfigs {
+        max_players: 2,
+        min_players: 2,
+        min_round_contribution: Some(100_000),
+        max_round_contribution: Some(1_000_000),
+        round_reward_multiplier: None,
+        post_round_actions: false,
+        round_timeout: None,
+        max_rounds: Some(1),
+        join_fee: None,
+        join_deadline: None,
+        is_rounds_based: false,
+        non_reveal_policy: NonRevealPolicy::ForfeitToPot,
+        tie_policy: TiePolicy::Replay,
+        elimination: false,
+        move_count: None,
+        router: None,
+        creator: None,
+        ratings: None,
+        protocol_fee_bps: 0,
+        payoff_formula: PayoffFormula::Linear,
+        action_ratios: ActionRatios::default(),
+    }
+}
+
+/// Validates the configurations of a dictator game.
+///
+/// The game is played by two players in a single round, and the endowment has to cover
+/// `min_round_contribution` before the game can start.
+pub fn validate_configs(configs: &GameConfigs) -> Result<(), GameError> {
+    configs.validate()?;
+
+    if configs.min_players != 2 || configs.max_players != 2 {
+        return Err(ConfigError::UnsupportedPlayerCount.into());
+    }
+    if configs.max_rounds != Some(1) {
+        return Err(ConfigError::UnsupportedRoundCount.into());
+    }
+    if configs.min_round_contribution.is_none() {
+        return Err(ConfigError::ContributionBoundsMissing.into());
+    }
+
+    Ok(())
+}
+
 #[openbrush::contract(env = CustomEnvironment)]
 mod dictator {
     use game_theory::ensure;
+    use game_theory::logics::impls::{
+        basic::data,
+        escrow::EscrowInternal,
+        lifecycle::{Hooks, LifecycleInternal},
+    };
     use game_theory::logics::traits::basic::*;
     use game_theory::logics::traits::types::{CustomEnvironment, RandomReadErr};
     use game_theory::logics::traits::types::{
-        GameConfigs, GameError, GameRound, GameStatus, RoundStatus,
+        GameConfigs, GameError, GameRound, GameStatus, RoundPayouts, RoundStatus,
     };
-    use game_theory::logics::traits::{admin::*, lifecycle::*};
+    use game_theory::logics::traits::{admin::*, escrow::*, lifecycle::*, upgradeable::*};
     use ink::codegen::Env;
-    use ink::env::hash::{Blake2x256, HashOutput};
     use ink::prelude::vec::Vec;
     use openbrush::{
         modifiers,
@@ -100,6 +156,71 @@ mod dictator {
         winners: Vec<(AccountId, u128)>,
         round_id: u8,
         total_contribution: u128,
+        /// The protocol fee taken from the winnings
+        protocol_fee: u128,
+    }
+
+    #[ink(event)]
+    pub struct RoundForceClosed {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        round_id: u8,
+        // represents the admin which took the action to force close the round
+        admin_id: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct PlayerLeft {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        player: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct GameCancelled {
+        #[ink(topic)]
+        game_address: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RouterNotificationFailed {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        router: AccountId,
+        // the selector of the `RouterCallbacks` message which failed
+        callback: [u8; 4],
+    }
+
+    #[ink(event)]
+    pub struct GameSettled {
+        #[ink(topic)]
+        game_address: AccountId,
+        // the refunds credited when the game is settled (join fees and unused endowments), or
+        // the remaining balance sent to the creator when the contract is terminated
+        transfers: Vec<(AccountId, Balance)>,
+    }
+
+    #[ink(event)]
+    pub struct BalanceClaimed {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        player: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PlayerPenalized {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        player: AccountId,
+        round_id: u8,
+        // the part of the round contribution lost for not revealing
+        penalty: u128,
     }
 
     #[ink(event)]
@@ -124,20 +245,9 @@ mod dictator {
     pub struct Dictator {
         #[storage_field]
         access: access_control::Data<enumerable::Members>,
-        /// Stores the list of players for this game instance
-        players: Vec<AccountId>,
-        /// The status of the current game
-        status: GameStatus,
-        /// A list of all the rounds that have been played
-        rounds: Vec<GameRound>,
-        /// The current round of the game
-        current_round: Option<GameRound>,
-        /// The id of the next round
-        next_round_id: u8,
-        /// Account that created the instance of this game
-        creator: AccountId,
-        /// The configurations of the game
-        configs: GameConfigs,
+        /// The shared game state (players, rounds, configs)
+        #[storage_field]
+        game: data::Data,
         /// current random seed
         seed: Option<[u8; 32]>,
         /// current round dictator
@@ -147,52 +257,38 @@ mod dictator {
     }
 
     impl Dictator {
-        #[ink(constructor)]
-        pub fn new(configs: GameConfigs) -> Self {
+        #[ink(constructor, payable)]
+        pub fn new(configs: GameConfigs) -> Result<Self, GameError> {
             let mut instance = Self {
                 access: Default::default(),
-                creator: <Self as DefaultEnv>::env().caller(),
-                players: Vec::new(),
-                status: GameStatus::Ready,
-                rounds: Vec::new(),
-                current_round: None,
-                next_round_id: 1,
-                configs: configs.clone(),
+                game: Default::default(),
                 seed: None,
                 dictator: None,
                 current_prize: None,
             };
             let caller = <Self as DefaultEnv>::env().caller();
 
-            // if let Some(max_rounds) = configs.max_rounds {
-            //     if max_rounds > 1 {
-            //         panic!("Dictator is not rounds based")
-            //     }
-            // }
-
+            // fails if the configs are not valid for this game, see `validate_configs`
+            instance._init_game(configs)?;
             instance._init_with_admin(caller);
             instance
                 .grant_role(CREATOR, caller)
                 .expect("Should grant CREATOR role");
+            // the account creating the game through the router shares the role with the router
+            let creator = instance.game.created_by;
+            if creator != caller {
+                instance
+                    .grant_role(CREATOR, creator)
+                    .expect("Should grant CREATOR role");
+            }
 
-            instance
+            Ok(instance)
         }
 
         /// Default constructor
         #[ink(constructor)]
         pub fn default() -> Self {
-            Self::new(GameConfigs {
-                max_players: 2,
-                min_players: 2,
-                min_round_contribution: Some(100_000),
-                max_round_contribution: Some(1_000_000),
-                round_reward_multiplier: None,
-                post_round_actions: false,
-                round_timeout: None,
-                max_rounds: Some(1),
-                join_fee: None,
-                is_rounds_based: false,
-            })
+            Self::new(crate::default_configs()).expect("Default configs should be valid")
         }
 
         #[ink(message)]
@@ -201,102 +297,40 @@ mod dictator {
 
             Ok(seed)
         }
-
-        // TODO: this is would be on the front end
-        #[ink(message)]
-        pub fn hash_commitment(&self, input: u128, nonce: u128) -> Result<Hash, GameError> {
-            let data = [input.to_le_bytes(), nonce.to_le_bytes()].concat();
-            let mut output = <Blake2x256 as HashOutput>::Type::default();
-            ink::env::hash_bytes::<Blake2x256>(&data, &mut output);
-            Ok(output.into())
-        }
     }
 
-    impl Basic for Dictator {
-        #[ink(message)]
-        fn get_configs(&self) -> GameConfigs {
-            self.configs.clone()
-        }
-
-        #[ink(message)]
-        fn get_players(&self) -> Vec<AccountId> {
-            self.players.clone()
-        }
+    impl Basic for Dictator {}
+    impl Lifecycle for Dictator {}
+    impl Escrow for Dictator {}
 
+    /// Only the creator of the game can migrate it to new code.
+    impl Upgradeable for Dictator {
         #[ink(message)]
-        fn get_status(&self) -> GameStatus {
-            self.status
+        #[modifiers(only_role(CREATOR))]
+        fn set_code_hash(&mut self, code_hash: Hash) -> Result<(), GameError> {
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| GameError::FailedToSetCodeHash)
         }
+    }
 
-        #[ink(message)]
-        fn get_current_round(&self) -> Option<GameRound> {
-            self.current_round.clone()
+    impl Hooks for Dictator {
+        fn _validate_configs(configs: &GameConfigs) -> Result<(), GameError> {
+            crate::validate_configs(configs)
         }
 
-        #[ink(message, payable)]
-        fn join(&mut self, player: AccountId) -> Result<u8, GameError> {
-            if Self::env().caller() != player {
-                return Err(GameError::CallerMustMatchNewPlayer);
-            }
-
-            if self.players.len() >= self.configs.max_players.into() {
-                return Err(GameError::MaxPlayersReached);
-            }
-
-            if self.players.contains(&player) {
-                return Err(GameError::PlayerAlreadyJoined);
-            };
-
-            if let Some(fees) = self.configs.join_fee {
-                if Self::env().transferred_value() < fees {
-                    return Err(GameError::InsufficientJoiningFees);
-                }
-            }
-
-            self.players.push(player);
-
-            ink::codegen::EmitEvent::<Dictator>::emit_event(
-                self.env(),
-                PlayerJoined {
-                    game_address: Self::env().account_id(),
-                    player: Self::env().caller(),
-                },
-            );
-
-            Ok(self.players.len() as u8)
+        /// Players don't contribute to the rounds, the prize comes from the game's endowment.
+        fn _validate_contribution(&self, _value: Balance) -> Result<(), GameError> {
+            Ok(())
         }
-    }
-
-    impl Lifecycle for Dictator {
-        #[ink(message, payable)]
-        fn start_game(&mut self) -> Result<(), GameError> {
-            if self.players.len() < self.configs.min_players.into() {
-                return Err(GameError::NotEnoughPlayers);
-            }
-
-            if self.status != GameStatus::Ready {
-                return Err(GameError::InvalidGameState);
-            }
-
-            self.current_round = Some(GameRound {
-                id: self.next_round_id,
-                status: RoundStatus::Ready,
-                player_commits: Vec::new(),
-                player_reveals: Vec::new(),
-                player_contributions: Vec::new(),
-                total_contribution: 0,
-                total_reward: 0,
-            });
-
-            self.status = GameStatus::OnGoing;
-            self.next_round_id += 1;
 
+        fn _on_game_started(&mut self) -> Result<(), GameError> {
             ensure!(
-                self.configs.min_round_contribution.is_some(),
+                self.game.configs.min_round_contribution.is_some(),
                 GameError::InvalidRoundContribution
             );
             ensure!(
-                self.env().balance() > self.configs.min_round_contribution.unwrap(),
+                self.env().balance() > self.game.configs.min_round_contribution.unwrap(),
                 GameError::BalanceNotEnough
             );
 
@@ -307,200 +341,199 @@ mod dictator {
                 .fetch_random(self.seed.unwrap())
                 .unwrap();
             let rand_int = u32::from_ne_bytes(new_random[0..4].try_into().unwrap());
-            let idx = rand_int as usize % self.players.len();
-            let dictator = self.players.get(idx).unwrap();
-            self.dictator = Some(*dictator);
+            let idx = rand_int as usize % self.game.players.len();
+            let dictator = *self.game.players.get(idx).unwrap();
+            self.dictator = Some(dictator);
 
             ink::codegen::EmitEvent::<Dictator>::emit_event(
                 self.env(),
                 DictatorChosen {
-                    dictator: *dictator,
+                    dictator,
                     endowment: self.env().balance(),
                 },
             );
 
-            ink::codegen::EmitEvent::<Dictator>::emit_event(
-                self.env(),
-                GameStarted {
-                    game_address: Self::env().account_id(),
-                    players: self.players.clone(),
-                },
-            );
-
             Ok(())
         }
 
-        #[ink(message, payable)]
-        fn play_round(&mut self, commitment: Hash) -> Result<(), GameError> {
-            if self.status != GameStatus::OnGoing {
-                return Err(GameError::InvalidGameState);
+        fn _on_reveal(&mut self, player: AccountId, reveal: (u128, u128)) -> Result<(), GameError> {
+            // we check if the dictator prize to each player
+            // sums up to the total endowment
+            if Some(player) == self.dictator {
+                let prize = reveal.0;
+                if prize * self.game.players.len() as u128 > self.env().balance() {
+                    return Err(GameError::InvalidReveal);
+                }
+                self.current_prize = Some(prize);
             }
 
-            if self.current_round.is_none() {
-                return Err(GameError::NoCurrentRound);
-            }
+            Ok(())
+        }
 
-            // Here we need to clone Self and then re-bind the current_round
-            // in the end of the method. Necessary so we can borrow self.env()
-            // immutably
-            let mut current_round = self.current_round.clone().unwrap();
-            // let current_round = self.current_round.as_mut().unwrap();
-            if current_round.status == RoundStatus::Ready {
-                current_round.status = RoundStatus::OnGoing
+        /// Players who did not reveal are treated as having declined the dictator's offer and
+        /// nothing is distributed if the dictator never revealed the prize. The dictator's own
+        /// reveal is the prize, never an acceptance.
+        fn _settle_round(&mut self, round: &GameRound) -> Result<RoundPayouts, GameError> {
+            let mut receivers: Vec<(AccountId, u128)> = Vec::new();
+
+            if let Some(current_prize) = self.current_prize {
+                for (caller, reveal) in round.player_reveals.iter() {
+                    if Some(*caller) != self.dictator && reveal.0 == 1 {
+                        receivers.push((*caller, current_prize));
+                    }
+                }
             }
 
-            let caller = Self::env().caller();
+            Ok(RoundPayouts::winnings(receivers))
+        }
 
-            if let Some(_p) = current_round
-                .player_commits
-                .iter()
-                .find(|(c, _)| c == &caller)
-            {
-                return Err(GameError::PlayerAlreadyCommitted);
-            }
+        fn _emit_player_joined_event(&self, player: AccountId) {
+            ink::codegen::EmitEvent::<Dictator>::emit_event(
+                self.env(),
+                PlayerJoined {
+                    game_address: self.env().account_id(),
+                    player,
+                },
+            );
+        }
 
-            current_round.player_commits.push((caller, commitment));
+        fn _emit_game_started_event(&self) {
+            ink::codegen::EmitEvent::<Dictator>::emit_event(
+                self.env(),
+                GameStarted {
+                    game_address: self.env().account_id(),
+                    players: self.game.players.clone(),
+                },
+            );
+        }
 
+        fn _emit_player_committed_event(&self, player: AccountId, commitment: Hash) {
             ink::codegen::EmitEvent::<Dictator>::emit_event(
                 self.env(),
                 PlayerCommitted {
-                    game_address: Self::env().account_id(),
-                    player: caller,
+                    game_address: self.env().account_id(),
+                    player,
                     commitment,
                 },
             );
-
-            // check if all players have committed
-            if current_round.player_commits.len() == self.players.len() {
-                ink::codegen::EmitEvent::<Dictator>::emit_event(
-                    self.env(),
-                    AllPlayersCommitted {
-                        game_address: Self::env().account_id(),
-                        commits: current_round.player_commits.clone(),
-                    },
-                )
-            }
-
-            // binding new current_round
-            self.current_round = Some(current_round);
-
-            Ok(())
         }
 
-        #[ink(message, payable)]
-        fn reveal_round(&mut self, reveal: (u128, u128)) -> Result<(), GameError> {
-            let caller = Self::env().caller();
-            let data = [reveal.0.to_le_bytes(), reveal.1.to_le_bytes()].concat();
-            let mut output = <Blake2x256 as HashOutput>::Type::default(); // 256 bit buffer
-            ink_env::hash_bytes::<Blake2x256>(&data, &mut output);
-
-            let mut current_round = self.current_round.clone().unwrap();
-
-            // we check if the dictator prize to each player
-            // sums up to the total endowment
-            if self.env().caller() == self.dictator.unwrap() {
-                let prize = reveal.0;
-                if prize * self.players.len() as u128 > self.env().balance() {
-                    return Err(GameError::InvalidReveal);
-                }
-                self.current_prize = Some(prize);
-            }
-
-            let player_commitment = current_round
-                .player_commits
-                .iter()
-                .find(|(c, _)| c == &caller);
-            if let Some(commit) = player_commitment {
-                if commit.1 != output.into() {
-                    return Err(GameError::InvalidReveal);
-                }
-            } else {
-                return Err(GameError::CommitmentNotFound);
-            }
-
-            current_round.player_reveals.push((caller, reveal));
+        fn _emit_all_players_committed_event(&self, round: &GameRound) {
+            ink::codegen::EmitEvent::<Dictator>::emit_event(
+                self.env(),
+                AllPlayersCommitted {
+                    game_address: self.env().account_id(),
+                    commits: round.player_commits.clone(),
+                },
+            );
+        }
 
+        fn _emit_player_revealed_event(&self, player: AccountId, reveal: (u128, u128)) {
             ink::codegen::EmitEvent::<Dictator>::emit_event(
                 self.env(),
                 PlayerRevealed {
-                    game_address: Self::env().account_id(),
-                    player: caller,
+                    game_address: self.env().account_id(),
+                    player,
                     reveal,
                 },
             );
-
-            self.current_round = Some(current_round);
-
-            Ok(())
         }
 
-        #[ink(message, payable)]
-        fn complete_round(&mut self) -> Result<(), GameError> {
-            let mut current_round = self.current_round.clone().unwrap();
-
-            if current_round.player_reveals.len() != self.players.len() {
-                return Err(GameError::NotAllPlayersRevealed);
-            };
-
-            if current_round.status != RoundStatus::OnGoing {
-                return Err(GameError::InvalidRoundState);
-            };
-
-            current_round.status = RoundStatus::Ended;
-
-            ensure!(self.current_prize.is_some(), GameError::EndowmentNotSet);
-            let current_prize = self.current_prize.unwrap();
-
-            let mut receivers: Vec<(AccountId, u128)> = Vec::new();
-
-            for (caller, reveal) in current_round.player_reveals.iter() {
-                if reveal.0 == 1 {
-                    let recv = (*caller, current_prize);
-                    self.env()
-                        .transfer(recv.0, recv.1)
-                        .map_err(|_| GameError::FailedToIssueWinnerRewards)?;
-                    receivers.push(recv);
-                }
-            }
-
+        fn _emit_round_completed_event(&self, round: &GameRound, payouts: &[(AccountId, u128)]) {
             ink::codegen::EmitEvent::<Dictator>::emit_event(
                 self.env(),
                 RoundEnded {
-                    game_address: Self::env().account_id(),
-                    winners: receivers,
-                    round_id: self.next_round_id,
-                    total_contribution: self.current_prize.unwrap(),
+                    game_address: self.env().account_id(),
+                    winners: payouts.to_vec(),
+                    round_id: round.id,
+                    total_contribution: self.current_prize.unwrap_or(0),
+                    protocol_fee: round.protocol_fee,
                 },
             );
+        }
 
-            self.current_round = Some(current_round);
+        fn _emit_round_force_closed_event(&self, round_id: u8, closed_by: AccountId) {
+            ink::codegen::EmitEvent::<Dictator>::emit_event(
+                self.env(),
+                RoundForceClosed {
+                    game_address: self.env().account_id(),
+                    round_id,
+                    admin_id: closed_by,
+                },
+            );
+        }
 
-            Ok(())
+        fn _emit_player_left_event(&self, player: AccountId) {
+            ink::codegen::EmitEvent::<Dictator>::emit_event(
+                self.env(),
+                PlayerLeft {
+                    game_address: self.env().account_id(),
+                    player,
+                },
+            );
         }
 
-        #[ink(message, payable)]
-        fn end_game(&mut self) -> Result<(), GameError> {
-            let current_round = self.current_round.as_mut().unwrap();
+        fn _emit_game_cancelled_event(&self) {
+            ink::codegen::EmitEvent::<Dictator>::emit_event(
+                self.env(),
+                GameCancelled {
+                    game_address: self.env().account_id(),
+                },
+            );
+        }
 
-            if self.status != GameStatus::OnGoing {
-                return Err(GameError::InvalidGameState);
-            }
+        fn _emit_game_settled_event(&self, transfers: &[(AccountId, u128)]) {
+            ink::codegen::EmitEvent::<Dictator>::emit_event(
+                self.env(),
+                GameSettled {
+                    game_address: self.env().account_id(),
+                    transfers: transfers.to_vec(),
+                },
+            );
+        }
 
-            if current_round.status != RoundStatus::Ended {
-                return Err(GameError::InvalidRoundState);
-            }
+        fn _emit_router_notification_failed_event(&self, router: AccountId, callback: [u8; 4]) {
+            ink::codegen::EmitEvent::<Dictator>::emit_event(
+                self.env(),
+                RouterNotificationFailed {
+                    game_address: self.env().account_id(),
+                    router,
+                    callback,
+                },
+            );
+        }
 
-            self.status = GameStatus::Ended;
+        fn _emit_claimed_event(&self, player: AccountId, amount: u128) {
+            ink::codegen::EmitEvent::<Dictator>::emit_event(
+                self.env(),
+                BalanceClaimed {
+                    game_address: self.env().account_id(),
+                    player,
+                    amount,
+                },
+            );
+        }
 
+        fn _emit_player_penalized_event(&self, round_id: u8, player: AccountId, penalty: u128) {
             ink::codegen::EmitEvent::<Dictator>::emit_event(
                 self.env(),
-                GameEnded {
-                    game_address: Self::env().account_id(),
-                    rounds_played: self.next_round_id,
+                PlayerPenalized {
+                    game_address: self.env().account_id(),
+                    player,
+                    round_id,
+                    penalty,
                 },
             );
+        }
 
-            Self::env().terminate_contract(self.creator);
+        fn _emit_game_ended_event(&self) {
+            ink::codegen::EmitEvent::<Dictator>::emit_event(
+                self.env(),
+                GameEnded {
+                    game_address: self.env().account_id(),
+                    rounds_played: self.game.next_round_id,
+                },
+            );
         }
     }
 
@@ -508,29 +541,14 @@ mod dictator {
         #[ink(message, payable)]
         #[modifiers(only_role(CREATOR))]
         fn add_player_to_game(&mut self, player: AccountId) -> Result<u8, GameError> {
-            // ensure that there's more room in the game
-            ensure!(
-                self.players.len() < self.configs.max_players as usize,
-                GameError::MaxPlayersReached
-            );
             // add player to state
-            self.players.push(player);
-            // any paid amount should be transferred to that particular player from the contract
+            let players_count = self._add_player(player)?;
+            // any paid amount is held as the player's deposit, to be refunded when the game ends
             let value = Self::env().transferred_value();
             if value > 0 {
-                Self::env()
-                    .transfer(player, Self::env().transferred_value())
-                    .map_err(|_| GameError::FailedToAddPlayer)?;
+                self.game.join_fees.push((player, value));
             }
-            // emit PlayerJoined event
-            ink::codegen::EmitEvent::<Dictator>::emit_event(
-                self.env(),
-                PlayerJoined {
-                    game_address: Self::env().account_id(),
-                    player,
-                },
-            );
-            Ok(self.players.len() as u8)
+            Ok(players_count)
         }
 
         #[ink(message)]
@@ -540,99 +558,60 @@ mod dictator {
             as_player: AccountId,
             commitment: Hash,
         ) -> Result<(), GameError> {
-            // TODO: refactor this logic into something re-usable for both admin and player
-
-            // ensure valid game state
-            ensure!(
-                self.status == GameStatus::OnGoing,
-                GameError::GameNotStarted
-            );
-            // ensure current round exists
-            ensure!(self.current_round.is_some(), GameError::NoCurrentRound);
-
-            let value = Self::env().transferred_value();
-            // NOTE: the issue of contribution amount privacy is discussed in the `play_round` method implementation.
-            // It's the reason we require the max_round_contribution amount here
-            ensure!(
-                value >= Balance::from(self.configs.max_round_contribution.unwrap_or(0)),
-                GameError::InvalidRoundContribution
-            );
-
-            let caller = as_player.clone();
-            let mut current_round = self.current_round.clone().unwrap();
-
-            // ensure that the player hasn't already made a commitment
-            ensure!(
-                current_round
-                    .player_commits
-                    .iter()
-                    .find(|(player, _)| player == &caller)
-                    .is_none(),
-                GameError::PlayerAlreadyCommitted
-            );
-
-            // store the commit
-            current_round
-                .player_commits
-                .push((as_player.clone(), commitment));
-
-            // keep track of round contribution(s)
-            current_round
-                .player_contributions
-                .push((as_player.clone(), value));
-
-            current_round.total_contribution += value;
-
-            // check if all players have committed
-            if current_round.player_commits.len() == self.players.len() {
-                ink::codegen::EmitEvent::<Dictator>::emit_event(
-                    self.env(),
-                    AllPlayersCommitted {
-                        game_address: Self::env().account_id(),
-                        commits: current_round.player_commits.clone(),
-                    },
-                );
-            }
-
-            ink::codegen::EmitEvent::<Dictator>::emit_event(
-                self.env(),
-                RoundCommitPlayed {
-                    game_address: Self::env().account_id(),
-                    player: caller,
-                    commitment,
-                },
-            );
-
-            self.current_round = Some(current_round);
-
-            Ok(())
+            self._commit(as_player, commitment, Self::env().transferred_value())
         }
 
         #[ink(message)]
         #[modifiers(only_role(CREATOR))]
-        fn reveal_round_as_player(&mut self) -> Result<(), GameError> {
-            todo!("implement")
+        fn reveal_round_as_player(
+            &mut self,
+            as_player: AccountId,
+            reveal: (u128, u128),
+        ) -> Result<(), GameError> {
+            self._reveal(as_player, reveal)
         }
 
         #[ink(message)]
         #[modifiers(only_role(CREATOR))]
         fn force_complete_round(&mut self) -> Result<(), GameError> {
-            todo!("implement")
+            self._force_close_round()
         }
 
         #[ink(message)]
         #[modifiers(only_role(CREATOR))]
         fn force_end_game(&mut self) -> Result<(), GameError> {
-            todo!("implement")
+            // close the current round if it's still open
+            if self.game.status == GameStatus::OnGoing
+                && self.game.current_round.as_ref().unwrap().status != RoundStatus::Ended
+            {
+                self._force_close_round()?;
+            }
+
+            if self.game.status != GameStatus::Ended {
+                self.game.status = GameStatus::Ended;
+                self._emit_game_ended_event();
+                self._notify_game_ended();
+            }
+
+            // refund the deposits, the contract is only terminated once every player has
+            // claimed their balance, `end_game` can be used otherwise
+            self._settle_game();
+            if self._is_settled() {
+                self._terminate_game()?;
+            }
+
+            Ok(())
         }
 
         #[ink(message, payable)]
         fn fund_contract(&mut self) -> Result<(), GameError> {
             let value = self.env().transferred_value();
             ensure!(
-                value > self.configs.min_round_contribution.unwrap(),
+                value > self.game.configs.min_round_contribution.unwrap(),
                 GameError::EndowmentNotEnough
             );
+            // keep track of the endowment, what's left of it is returned when the game ends
+            self.game.endowments.push((self.env().caller(), value));
 
             ink::codegen::EmitEvent::<Dictator>::emit_event(
                 self.env(),
@@ -651,11 +630,209 @@ mod dictator {
     mod tests {
         /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
+        use game_theory::logics::traits::types::ConfigError;
+
+        type Environment = ink::env::DefaultEnvironment;
+
+        /// Mocks the randomness extension, always returning `random`.
+        struct MockedRandom {
+            random: [u8; 32],
+        }
+
+        impl ink::env::test::ChainExtension for MockedRandom {
+            fn func_id(&self) -> u32 {
+                12
+            }
+
+            fn call(&mut self, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                scale::Encode::encode_to(&self.random, output);
+                0
+            }
+        }
+
+        fn get_accounts() -> ink::env::test::DefaultAccounts<Environment> {
+            ink::env::test::default_accounts::<Environment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            ink::env::test::set_caller::<Environment>(account);
+        }
+
+        /// Starts a game between alice and bob in which alice is the dictator, with an
+        /// endowment of 1_000_000.
+        fn start_game() -> Dictator {
+            let accounts = get_accounts();
+            ink::env::test::register_chain_extension(MockedRandom { random: [0; 32] });
+            ink::env::test::set_account_balance::<Environment>(
+                ink::env::test::callee::<Environment>(),
+                1_000_000,
+            );
+
+            set_caller(accounts.alice);
+            let mut dictator = Dictator::default();
+            assert!(dictator.join(accounts.alice).is_ok());
+            set_caller(accounts.bob);
+            assert!(dictator.join(accounts.bob).is_ok());
+            assert!(dictator.set_random_seed([7; 32]).is_ok());
+            assert!(dictator.start_game().is_ok());
+            assert_eq!(dictator.dictator, Some(accounts.alice));
+
+            dictator
+        }
+
+        /// Commits to `choice` as `player`, returning the reveal to make.
+        fn commit(dictator: &mut Dictator, player: AccountId, choice: u128) -> (u128, u128) {
+            let reveal = (choice, 42);
+            let commitment = dictator
+                .hash_commitment(player, reveal.0, reveal.1)
+                .unwrap();
+            set_caller(player);
+            assert!(dictator.play_round(commitment).is_ok());
+            reveal
+        }
 
         /// We test if the default constructor does its job.
         #[ink::test]
         fn default_works() {
-            let mut dictator = Dictator::default();
+            let dictator = Dictator::default();
+            assert_eq!(dictator.game.configs, crate::default_configs());
+        }
+
+        /// The game is played by two players in a single round.
+        #[ink::test]
+        fn new_rejects_invalid_configs() {
+            let mut configs = crate::default_configs();
+            configs.max_players = 3;
+            assert_eq!(
+                Dictator::new(configs.clone()).err(),
+                Some(GameError::InvalidConfig(
+                    ConfigError::UnsupportedPlayerCount
+                ))
+            );
+
+            configs.max_players = 2;
+            configs.max_rounds = Some(2);
+            assert_eq!(
+                Dictator::new(configs).err(),
+                Some(GameError::InvalidConfig(ConfigError::UnsupportedRoundCount))
+            );
+        }
+
+        /// The players who accept the offer get the prize, the round completed event carries
+        /// the id of the round.
+        #[ink::test]
+        fn accepting_players_get_the_prize() {
+            let accounts = get_accounts();
+            let mut dictator = start_game();
+
+            let offer = commit(&mut dictator, accounts.alice, 200_000);
+            let accept = commit(&mut dictator, accounts.bob, 1);
+            set_caller(accounts.alice);
+            assert!(dictator.reveal_round(offer).is_ok());
+            set_caller(accounts.bob);
+            assert!(dictator.reveal_round(accept).is_ok());
+            assert!(dictator.complete_round().is_ok());
+
+            assert_eq!(dictator.get_claimable(accounts.bob), 200_000);
+            assert_eq!(dictator.get_claimable(accounts.alice), 0);
+            assert_eq!(dictator.get_status(), GameStatus::Ended);
+
+            let round_id = ink::env::test::recorded_events().find_map(|e| {
+                match <Event as scale::Decode>::decode(&mut &e.data[..]) {
+                    Ok(Event::RoundEnded(data)) => Some(data.round_id),
+                    _ => None,
+                }
+            });
+            assert_eq!(round_id, Some(1));
+        }
+
+        /// A prize of 1 is not an acceptance from the dictator.
+        #[ink::test]
+        fn dictator_does_not_get_the_prize() {
+            let accounts = get_accounts();
+            let mut dictator = start_game();
+
+            let offer = commit(&mut dictator, accounts.alice, 1);
+            let accept = commit(&mut dictator, accounts.bob, 1);
+            set_caller(accounts.alice);
+            assert!(dictator.reveal_round(offer).is_ok());
+            set_caller(accounts.bob);
+            assert!(dictator.reveal_round(accept).is_ok());
+            assert!(dictator.complete_round().is_ok());
+
+            assert_eq!(dictator.get_claimable(accounts.alice), 0);
+            assert_eq!(dictator.get_claimable(accounts.bob), 1);
+        }
+
+        /// Only the creator can reveal on behalf of a player.
+        #[ink::test]
+        fn creator_can_reveal_as_player() {
+            let accounts = get_accounts();
+            let mut dictator = start_game();
+
+            let offer = commit(&mut dictator, accounts.alice, 200_000);
+            let accept = commit(&mut dictator, accounts.bob, 1);
+
+            set_caller(accounts.bob);
+            assert!(dictator
+                .reveal_round_as_player(accounts.alice, offer)
+                .is_err());
+
+            set_caller(accounts.alice);
+            assert!(dictator.reveal_round(offer).is_ok());
+            assert!(dictator
+                .reveal_round_as_player(accounts.bob, accept)
+                .is_ok());
+            assert!(dictator.complete_round().is_ok());
+            assert_eq!(dictator.get_claimable(accounts.bob), 200_000);
+        }
+
+        /// The creator can close the round without the reveal of every player, the players who
+        /// didn't reveal declined the offer.
+        #[ink::test]
+        fn creator_can_force_complete_round() {
+            let accounts = get_accounts();
+            let mut dictator = start_game();
+
+            let offer = commit(&mut dictator, accounts.alice, 200_000);
+            commit(&mut dictator, accounts.bob, 1);
+            set_caller(accounts.alice);
+            assert!(dictator.reveal_round(offer).is_ok());
+            assert_eq!(
+                dictator.complete_round(),
+                Err(GameError::NotAllPlayersRevealed)
+            );
+
+            set_caller(accounts.bob);
+            assert!(dictator.force_complete_round().is_err());
+
+            set_caller(accounts.alice);
+            assert!(dictator.force_complete_round().is_ok());
+            assert_eq!(dictator.get_status(), GameStatus::Ended);
+            assert_eq!(dictator.get_claimable(accounts.bob), 0);
+        }
+
+        /// Force ending the game closes the open round, the contract is only terminated once
+        /// the prizes are claimed.
+        #[ink::test]
+        fn creator_can_force_end_game() {
+            let accounts = get_accounts();
+            let mut dictator = start_game();
+
+            let offer = commit(&mut dictator, accounts.alice, 200_000);
+            let accept = commit(&mut dictator, accounts.bob, 1);
+            set_caller(accounts.alice);
+            assert!(dictator.reveal_round(offer).is_ok());
+            set_caller(accounts.bob);
+            assert!(dictator.reveal_round(accept).is_ok());
+
+            assert!(dictator.force_end_game().is_err());
+            set_caller(accounts.alice);
+            assert!(dictator.force_end_game().is_ok());
+
+            assert_eq!(dictator.get_status(), GameStatus::Ended);
+            assert_eq!(dictator.get_claimable(accounts.bob), 200_000);
+            assert_eq!(dictator.end_game(), Err(GameError::BalancesNotSettled));
         }
     }
 
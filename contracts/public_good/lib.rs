@@ -3,17 +3,73 @@
 
 pub use self::public_good::{PublicGood, PublicGoodRef};
 
+use game_theory::logics::traits::types::{
+    ActionRatios, ConfigError, GameConfigs, GameError, NonRevealPolicy, PayoffFormula, TiePolicy,
+};
+
+/// The configurations used by the `default` constructor: up to 10 players over 3 rounds.
+pub fn default_configs() -> GameConfigs {
+    GameConfigs {
+        max_players: 10,
+        min_players: 2,
+        min_round_contribution: Some(100),
+        max_round_contribution: Some(1_000),
+        round_reward_multiplier: Some(20),
+        post_round_actions: false,
+        round_timeout: None,
+        max_rounds: Some(3),
+        join_fee: None,
+        join_deadline: None,
+        is_rounds_based: false,
+        non_reveal_policy: NonRevealPolicy::ForfeitToPot,
+        tie_policy: TiePolicy::Replay,
+        elimination: false,
+        move_count: None,
+        router: None,
+        creator: None,
+        ratings: None,
+        protocol_fee_bps: 0,
+        payoff_formula: PayoffFormula::Linear,
+        action_ratios: ActionRatios::default(),
+    }
+}
+
+/// Validates the configurations of a public good game.
+///
+/// Players contribute up to `max_round_contribution` and are rewarded using the
+/// `round_reward_multiplier`, so both must be set.
+pub fn validate_configs(configs: &GameConfigs) -> Result<(), GameError> {
+    configs.validate()?;
+
+    if configs.min_round_contribution.is_none() || configs.max_round_contribution.is_none() {
+        return Err(ConfigError::ContributionBoundsMissing.into());
+    }
+    if configs.round_reward_multiplier.is_none() {
+        return Err(ConfigError::RewardMultiplierMissing.into());
+    }
+
+    Ok(())
+}
+
 // noinspection ALL
 #[openbrush::contract]
 pub mod public_good {
     use game_theory::ensure;
+    use game_theory::logics::commitment::hash_actions_commitment;
+    use game_theory::logics::impls::{
+        basic::data,
+        escrow::EscrowInternal,
+        lifecycle::{Hooks, LifecycleInternal},
+    };
     use game_theory::logics::traits::types::{
-        GameConfigs, GameError, GameRound, GameStatus, RoundStatus,
+        ActionRatios, GameConfigs, GameError, GameRound, GameStatus, PointsAssignment,
+        RoundPayouts, RoundStatus,
+    };
+    use game_theory::logics::traits::{
+        admin::*, basic::*, escrow::*, lifecycle::*, upgradeable::*, utils::*,
     };
-    use game_theory::logics::traits::{admin::*, basic::*, lifecycle::*, utils::*};
     use ink::codegen::EmitEvent;
     use ink::codegen::Env;
-    use ink::env::hash::{Blake2x256, HashOutput};
     use ink::prelude::vec::Vec;
     use openbrush::contracts::access_control::{extensions::enumerable::*, only_role};
     use openbrush::{
@@ -85,6 +141,59 @@ pub mod public_good {
         admin_id: AccountId,
     }
 
+    #[ink(event)]
+    pub struct PlayerLeft {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        player: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct GameCancelled {
+        #[ink(topic)]
+        game_address: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RouterNotificationFailed {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        router: AccountId,
+        // the selector of the `RouterCallbacks` message which failed
+        callback: [u8; 4],
+    }
+
+    #[ink(event)]
+    pub struct GameSettled {
+        #[ink(topic)]
+        game_address: AccountId,
+        // the refunds credited when the game is settled (join fees and unused endowments), or
+        // the remaining balance sent to the creator when the contract is terminated
+        transfers: Vec<(AccountId, Balance)>,
+    }
+
+    #[ink(event)]
+    pub struct BalanceClaimed {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        player: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PlayerPenalized {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        player: AccountId,
+        round_id: u8,
+        // the part of the round contribution lost for not revealing
+        penalty: u128,
+    }
+
     #[ink(event)]
     pub struct RoundCompleted {
         #[ink(topic)]
@@ -92,6 +201,8 @@ pub mod public_good {
         #[ink(topic)]
         round_id: u8,
         winners: Vec<(AccountId, Option<u128>)>,
+        /// The protocol fee taken from the winnings
+        protocol_fee: u128,
     }
 
     #[ink(event)]
@@ -100,6 +211,126 @@ pub mod public_good {
         game_address: AccountId,
     }
 
+    #[ink(event)]
+    pub struct ActionsCommitted {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        player: AccountId,
+        round_id: u8,
+        commitment: Hash,
+    }
+
+    #[ink(event)]
+    pub struct PointsAssigned {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        round_id: u8,
+        punishment: u32,
+        reward: u32,
+        // what the points cost to the player assigning them
+        cost: u128,
+        // what the points take from and add to the payout of the player receiving them
+        impact: (u128, u128),
+    }
+
+    #[ink(event)]
+    pub struct ActionsCompleted {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        round_id: u8,
+        // the payouts of the round, adjusted by the points assigned
+        payouts: Vec<(AccountId, u128)>,
+        /// The protocol fee taken from the payouts
+        protocol_fee: u128,
+    }
+
+    /// The stage following a round in which the members of the group assign costly punishment
+    /// and reward points to each other, see `GameConfigs::post_round_actions`.
+    #[derive(scale::Encode, scale::Decode, PartialEq, Eq, Clone, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct ActionStage {
+        pub round_id: u8,
+        /// The payouts of the round for each member of the group, released once the stage
+        /// is completed
+        pub payouts: Vec<(AccountId, u128)>,
+        pub commits: Vec<(AccountId, Hash)>,
+        pub reveals: Vec<(AccountId, Vec<PointsAssignment>)>,
+        /// The last block at which commitments are accepted (if the game has a `round_timeout`).
+        pub commit_deadline: Option<u32>,
+        /// The last block at which reveals are accepted (if the game has a `round_timeout`).
+        pub reveal_deadline: Option<u32>,
+    }
+
+    impl ActionStage {
+        /// Opens the stage following round `round_id`, holding its `payouts`.
+        ///
+        /// Like a round, the stage has a commit phase and a reveal phase of `round_timeout`
+        /// blocks each, if set.
+        pub fn new(
+            round_id: u8,
+            payouts: Vec<(AccountId, u128)>,
+            start_block: u32,
+            round_timeout: Option<u32>,
+        ) -> Self {
+            Self {
+                round_id,
+                payouts,
+                commits: Vec::new(),
+                reveals: Vec::new(),
+                commit_deadline: round_timeout.map(|timeout| start_block.saturating_add(timeout)),
+                reveal_deadline: round_timeout
+                    .map(|timeout| start_block.saturating_add(timeout.saturating_mul(2))),
+            }
+        }
+
+        pub fn is_member(&self, player: &AccountId) -> bool {
+            self.payouts.iter().any(|(member, _)| member == player)
+        }
+
+        /// Returns the payout of the round held for `player`.
+        pub fn payout_of(&self, player: &AccountId) -> u128 {
+            self.payouts
+                .iter()
+                .find(|(member, _)| member == player)
+                .map_or(0, |(_, payout)| *payout)
+        }
+
+        /// Returns the payouts of the round adjusted by the points revealed: each member pays
+        /// for the points they assigned, then gets the impact of the points assigned to them.
+        /// A payout can't go below zero.
+        pub fn adjusted_payouts(&self, ratios: &ActionRatios) -> Vec<(AccountId, u128)> {
+            self.payouts
+                .iter()
+                .map(|(member, payout)| {
+                    let mut gains = *payout;
+                    let mut losses = 0u128;
+                    for (from, actions) in self.reveals.iter() {
+                        for assignment in actions.iter() {
+                            if from == member {
+                                losses = losses.saturating_add(ratios.cost(assignment));
+                            }
+                            if &assignment.to == member {
+                                let (taken, added) = ratios.impact(assignment);
+                                losses = losses.saturating_add(taken);
+                                gains = gains.saturating_add(added);
+                            }
+                        }
+                    }
+                    (*member, gains.saturating_sub(losses))
+                })
+                .collect()
+        }
+    }
+
     /// A single game storage.
     /// Each contract (along with its storage) represents a single game instance.
     #[ink(storage)]
@@ -108,70 +339,45 @@ pub mod public_good {
         /// openbrush access control storage
         #[storage_field]
         access: access_control::Data<Members>,
-        created_by: AccountId,
-        /// Stores the list of players for this game instance
-        players: Vec<AccountId>,
-        /// The status of the current game
-        status: GameStatus,
-        /// The current round of the game
-        current_round: Option<GameRound>,
-        /// The id of the next round
-        next_round_id: u8,
-        /// The configurations of the game
-        configs: GameConfigs,
+        /// The shared game state (players, rounds, configs)
+        #[storage_field]
+        game: data::Data,
+        /// The stage following the last round, until it's completed
+        action_stage: Option<ActionStage>,
     }
 
     impl PublicGood {
         /// Constructor that initializes the PublicGood struct
-        #[ink(constructor)]
-        pub fn new(configs: GameConfigs) -> Self {
-            // basic sanity checks related to round contributions for this game
-            if configs.max_round_contribution.is_none() {
-                panic!("The max_round_contribution must be set");
-            } else if configs.min_round_contribution.is_none() {
-                panic!("The min_round_contribution must be set");
-            } else if configs.max_round_contribution.unwrap()
-                < configs.min_round_contribution.unwrap()
-            {
-                panic!(
-                    "The max_round_contribution must be greater than the min_round_contribution"
-                );
-            }
-
+        #[ink(constructor, payable)]
+        pub fn new(configs: GameConfigs) -> Result<Self, GameError> {
             let mut instance = Self {
                 access: Default::default(),
-                created_by: <Self as DefaultEnv>::env().caller(),
-                players: Vec::new(),
-                status: GameStatus::Ready,
-                current_round: None,
-                next_round_id: 1,
-                configs,
+                game: Default::default(),
+                action_stage: None,
             };
 
             let caller = <Self as DefaultEnv>::env().caller();
+            // fails if the configs are not valid for this game, see `validate_configs`
+            instance._init_game(configs)?;
             instance._init_with_admin(caller);
             instance
                 .grant_role(CREATOR, caller)
                 .expect("Should grant CREATOR role");
+            // the account creating the game through the router shares the role with the router
+            let creator = instance.game.created_by;
+            if creator != caller {
+                instance
+                    .grant_role(CREATOR, creator)
+                    .expect("Should grant CREATOR role");
+            }
 
-            instance
+            Ok(instance)
         }
 
         /// A default constructor that initializes this game with 10 players.
         #[ink(constructor)]
         pub fn default() -> Self {
-            Self::new(GameConfigs {
-                max_players: 10,
-                min_players: 2,
-                min_round_contribution: Some(100),
-                max_round_contribution: Some(1_000),
-                round_reward_multiplier: Some(20),
-                post_round_actions: false,
-                round_timeout: None,
-                max_rounds: Some(3),
-                join_fee: None,
-                is_rounds_based: false,
-            })
+            Self::new(crate::default_configs()).expect("Default configs should be valid")
         }
 
         /// Helper methods
@@ -199,319 +405,461 @@ pub mod public_good {
             Ok(())
         }
 
-        // TODO: this is would be on the front end
-        #[ink(message)]
-        pub fn hash_commitment(&self, input: u128, nonce: u128) -> Result<Hash, GameError> {
-            let data = [input.to_le_bytes(), nonce.to_le_bytes()].concat();
-            let mut output = <Blake2x256 as HashOutput>::Type::default();
-            ink::env::hash_bytes::<Blake2x256>(&data, &mut output);
-            Ok(output.into())
-        }
-    }
-
-    /// Add default implementation for access control to the game
-    impl AccessControl for PublicGood {}
-    impl AccessControlEnumerable for PublicGood {}
-
-    /// An implementation of the basic ink! storage getters / setters
-    impl Basic for PublicGood {
-        #[ink(message)]
-        fn get_configs(&self) -> GameConfigs {
-            self.configs.clone()
-        }
-
+        /// Get the stage following the last round, while it isn't completed.
         #[ink(message)]
-        fn get_players(&self) -> Vec<AccountId> {
-            self.players.clone()
+        pub fn get_action_stage(&self) -> Option<ActionStage> {
+            self.action_stage.clone()
         }
 
+        /// Computes the commitment to the points `actions` assigned by `player` in the current
+        /// action stage.
         #[ink(message)]
-        fn get_status(&self) -> GameStatus {
-            self.status.clone()
+        pub fn hash_actions_commitment(
+            &self,
+            player: AccountId,
+            actions: Vec<PointsAssignment>,
+            nonce: u128,
+        ) -> Result<Hash, GameError> {
+            let stage = self.action_stage.as_ref().ok_or(GameError::NoActionStage)?;
+
+            Ok(hash_actions_commitment(
+                &self.env().account_id(),
+                stage.round_id,
+                &player,
+                &actions,
+                nonce,
+            ))
         }
 
+        /// Commits to the points the caller assigns in the current action stage, only the
+        /// members of the group of the last round take part in it.
         #[ink(message)]
-        fn get_current_round(&self) -> Option<GameRound> {
-            self.current_round.clone()
-        }
+        pub fn commit_actions(&mut self, commitment: Hash) -> Result<(), GameError> {
+            let player = self.env().caller();
+            let block = self.env().block_number();
+            let game_address = self.env().account_id();
+            let stage = self.action_stage.as_mut().ok_or(GameError::NoActionStage)?;
 
-        #[ink(message, payable)]
-        fn join(&mut self, player: AccountId) -> Result<u8, GameError> {
-            // ensure that joining is only done by caller
+            ensure!(stage.is_member(&player), GameError::PlayerNotInGame);
+            ensure!(
+                stage
+                    .commit_deadline
+                    .map_or(true, |deadline| block <= deadline),
+                GameError::CommitDeadlinePassed
+            );
             ensure!(
-                Self::env().caller() == player,
-                GameError::CallerMustMatchNewPlayer
+                !stage.commits.iter().any(|(p, _)| p == &player),
+                GameError::PlayerAlreadyCommitted
             );
-            // ensure that there's more room in the game
             ensure!(
-                self.players.len() < self.configs.max_players as usize,
-                GameError::MaxPlayersReached
+                !stage.commits.iter().any(|(_, c)| c == &commitment),
+                GameError::DuplicateCommitment
             );
-            // ensure applicable fees are paid
-            if let Some(fees) = self.configs.join_fee {
-                ensure!(
-                    Self::env().transferred_value() >= Balance::from(fees),
-                    GameError::InsufficientJoiningFees
-                );
-            }
-            // add player to state
-            self.players.push(player);
-            // emit PlayerJoined event
-            Self::env().emit_event(PlayerJoined {
-                game_address: Self::env().account_id(),
+
+            stage.commits.push((player, commitment));
+            let round_id = stage.round_id;
+
+            self.env().emit_event(ActionsCommitted {
+                game_address,
                 player,
+                round_id,
+                commitment,
             });
-            Ok(self.players.len() as u8)
+
+            Ok(())
         }
-    }
 
-    /// An implementation of the `GameLifecycle` trait for the `PublicGood` contract.
-    impl Lifecycle for PublicGood {
-        #[ink(message, payable)]
-        fn start_game(&mut self) -> Result<(), GameError> {
-            // ensure game status is valid for state change
+        /// Reveals the points the caller committed to in the current action stage.
+        ///
+        /// Points can only be assigned to the other members of the group, and can't cost more
+        /// than the caller's payout for the round.
+        #[ink(message)]
+        pub fn reveal_actions(
+            &mut self,
+            actions: Vec<PointsAssignment>,
+            nonce: u128,
+        ) -> Result<(), GameError> {
+            let player = self.env().caller();
+            let block = self.env().block_number();
+            let game_address = self.env().account_id();
+            let ratios = self.game.configs.action_ratios;
+            let stage = self.action_stage.as_mut().ok_or(GameError::NoActionStage)?;
+
             ensure!(
-                self.status == GameStatus::Ready,
-                GameError::InvalidGameState
+                stage
+                    .reveal_deadline
+                    .map_or(true, |deadline| block <= deadline),
+                GameError::RevealDeadlinePassed
             );
-            // ensure enough players
+            let commitment = stage
+                .commits
+                .iter()
+                .find(|(p, _)| p == &player)
+                .map(|(_, c)| *c)
+                .ok_or(GameError::CommitmentNotFound)?;
             ensure!(
-                self.players.len() >= self.configs.min_players as usize,
-                GameError::NotEnoughPlayers
+                commitment
+                    == hash_actions_commitment(
+                        &game_address,
+                        stage.round_id,
+                        &player,
+                        &actions,
+                        nonce
+                    ),
+                GameError::InvalidReveal
             );
-
-            // setup the current round
-            self.current_round = Some(GameRound {
-                id: self.next_round_id,
-                status: RoundStatus::Ready,
-                player_commits: Vec::new(),
-                player_reveals: Vec::new(),
-                player_contributions: Vec::new(),
-                total_contribution: 0,
-                total_reward: 0,
-            });
-            self.next_round_id += 1;
-            // update game state
-            self.status = GameStatus::OnGoing;
-            // emit GameStarted event
-            Self::env().emit_event(GameStarted {
-                game_address: Self::env().account_id(),
-            });
-            Ok(())
-        }
-
-        #[ink(message, payable)]
-        fn play_round(&mut self, commitment: Hash) -> Result<(), GameError> {
-            // ensure valid game state
             ensure!(
-                self.status == GameStatus::OnGoing,
-                GameError::GameNotStarted
+                !stage.reveals.iter().any(|(p, _)| p == &player),
+                GameError::PlayerAlreadyRevealed
             );
-            // ensure current round exists
-            ensure!(self.current_round.is_some(), GameError::NoCurrentRound);
-
-            let value = Self::env().transferred_value();
-            // NOTE: the issue here is since this game is publicgood, some amount has to be
-            // contributed to the pot. So, we need to check if the player has contributed
-            // that amount. But we also don't want to reveal the contribution :)
-            // one way is to have the payable amount always be fixed and be maxed out
-            // while the hashed commitment contains the real amount to be contributed.
             ensure!(
-                value >= Balance::from(self.configs.max_round_contribution.unwrap_or(0)),
-                GameError::InvalidRoundContribution
+                actions
+                    .iter()
+                    .all(|assignment| assignment.to != player && stage.is_member(&assignment.to)),
+                GameError::InvalidChoice
+            );
+            let cost = actions.iter().fold(0u128, |cost, assignment| {
+                cost.saturating_add(ratios.cost(assignment))
+            });
+            ensure!(
+                cost <= stage.payout_of(&player),
+                GameError::BalanceNotEnough
             );
 
-            let caller = Self::env().caller();
-            let current_round = self.current_round.as_mut().unwrap();
+            stage.reveals.push((player, actions.clone()));
+            let round_id = stage.round_id;
+
+            for assignment in actions {
+                self.env().emit_event(PointsAssigned {
+                    game_address,
+                    from: player,
+                    to: assignment.to,
+                    round_id,
+                    punishment: assignment.punishment,
+                    reward: assignment.reward,
+                    cost: ratios.cost(&assignment),
+                    impact: ratios.impact(&assignment),
+                });
+            }
+
+            Ok(())
+        }
 
-            // ensure that the player hasn't already made a commitment
+        /// Completes the current action stage, crediting the payouts of the round adjusted by
+        /// the points assigned (see `ActionStage::adjusted_payouts`).
+        ///
+        /// Can be called once every member of the group revealed, once the reveal deadline has
+        /// passed, or at any time by the creator of the game. The deadlines of the next round
+        /// start once the stage is completed, since it can't be played before.
+        #[ink(message)]
+        pub fn complete_actions(&mut self) -> Result<(), GameError> {
+            let stage = self.action_stage.as_ref().ok_or(GameError::NoActionStage)?;
+            let expired = stage
+                .reveal_deadline
+                .map_or(false, |deadline| self.env().block_number() > deadline);
             ensure!(
-                current_round
-                    .player_commits
-                    .iter()
-                    .find(|(player, _)| player == &caller)
-                    .is_none(),
-                GameError::PlayerAlreadyCommitted
+                stage.reveals.len() == stage.payouts.len()
+                    || expired
+                    || self.has_role(CREATOR, self.env().caller()),
+                GameError::NotAllPlayersRevealed
             );
 
-            // store the commit
-            current_round
-                .player_commits
-                .push((caller.clone(), commitment));
-
-            // keep track of round contribution(s)
-            current_round
-                .player_contributions
-                .push((caller.clone(), value));
+            let mut payouts = stage.adjusted_payouts(&self.game.configs.action_ratios);
+            let round_id = stage.round_id;
+            self.action_stage = None;
 
-            current_round.total_contribution += value;
+            let protocol_fee = self._collect_protocol_fee(&mut payouts);
+            for (player, amount) in payouts.iter() {
+                self._credit(*player, *amount);
+            }
+            if protocol_fee > 0 {
+                self._notify_round_completed(round_id, protocol_fee);
+            }
 
-            // check if all players have committed
-            if current_round.player_commits.len() == self.players.len() {
-                Self::env().emit_event(AllPlayersCommitted {
-                    game_address: Self::env().account_id(),
-                    round_id: current_round.id.clone(),
-                });
+            // restart the next round (if any), nobody could commit to it during the stage
+            if self.game.status == GameStatus::OnGoing {
+                let block = self.env().block_number();
+                if let Some(round) = self.game.current_round.as_mut() {
+                    if round.id != round_id && round.status == RoundStatus::Ready {
+                        *round = GameRound::new(round.id, block, &self.game.configs);
+                    }
+                }
             }
 
-            Self::env().emit_event(RoundCommitPlayed {
-                game_address: Self::env().account_id(),
-                player: Self::env().caller(),
-                commitment,
+            self.env().emit_event(ActionsCompleted {
+                game_address: self.env().account_id(),
+                round_id,
+                payouts,
+                protocol_fee,
             });
+
             Ok(())
         }
+    }
 
-        #[ink(message, payable)]
-        fn reveal_round(&mut self, reveal: (u128, u128)) -> Result<(), GameError> {
-            let caller = Self::env().caller();
-            let data = [reveal.0.to_le_bytes(), reveal.1.to_le_bytes()].concat();
-            let mut output = <Blake2x256 as HashOutput>::Type::default();
-            ink::env::hash_bytes::<Blake2x256>(&data, &mut output);
+    /// Add default implementation for access control to the game
+    impl AccessControl for PublicGood {}
+    impl AccessControlEnumerable for PublicGood {}
 
-            let player_commitment = self
-                .current_round
-                .as_ref()
-                .unwrap()
-                .player_commits
-                .iter()
-                .find(|(player, _)| player == &caller);
+    /// The basic ink! storage getters / setters and the game lifecycle use the default
+    /// implementations from `game_theory::logics::impls`.
+    impl Basic for PublicGood {}
+    impl Lifecycle for PublicGood {
+        /// Rounds can't be played until the stage following the last round is completed.
+        #[ink(message)]
+        fn play_round(&mut self, commitment: Hash) -> Result<(), GameError> {
+            ensure!(self.action_stage.is_none(), GameError::ActionStageOngoing);
+
+            self._commit(
+                Self::env().caller(),
+                commitment,
+                Self::env().transferred_value(),
+            )
+        }
 
-            // ensure that the commitment exists
-            ensure!(player_commitment.is_some(), GameError::CommitmentNotFound);
-            // ensure that the reveal is valid
+        /// The game can't be ended until the stage following the last round is completed,
+        /// since its payouts are still held.
+        #[ink(message)]
+        fn end_game(&mut self) -> Result<(), GameError> {
+            ensure!(self.action_stage.is_none(), GameError::ActionStageOngoing);
+            // ensure the game is in ended (or cancelled) state
             ensure!(
-                player_commitment.unwrap().1 == output.into(),
-                GameError::InvalidReveal
+                self.game.status == GameStatus::Ended || self.game.status == GameStatus::Cancelled,
+                GameError::InvalidGameState
             );
 
-            // return the partial contribution to the player
-            // this is done because all players contribute the max amount when making a commitment
-            // to avoid information leakage
-            Self::env()
-                .transfer(
-                    caller,
-                    self.configs.max_round_contribution.unwrap() - reveal.0,
-                )
-                .map_err(|_| GameError::PartialContributionRefundFailed)?;
-
-            // store the reveal
-            self.current_round
-                .as_mut()
-                .unwrap()
-                .player_reveals
-                .push((caller, reveal));
-            // emit event
-            self.env().emit_event(RoundCommitRevealed {
-                game_address: Self::env().account_id(),
-                player: Self::env().caller(),
-                reveal: Some(reveal),
-            });
-
-            Ok(())
+            // refund the deposits and terminate the contract
+            self._terminate_game()
         }
+    }
+    impl Escrow for PublicGood {}
 
+    /// Only the creator of the game can migrate it to new code.
+    impl Upgradeable for PublicGood {
         #[ink(message)]
-        fn complete_round(&mut self) -> Result<(), GameError> {
-            let current_round = self.current_round.as_mut().unwrap();
+        #[modifiers(only_role(CREATOR))]
+        fn set_code_hash(&mut self, code_hash: Hash) -> Result<(), GameError> {
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| GameError::FailedToSetCodeHash)
+        }
+    }
 
-            // ensure all players have revealed
+    /// Public good specific behaviour plugged into the default lifecycle.
+    impl Hooks for PublicGood {
+        fn _validate_configs(configs: &GameConfigs) -> Result<(), GameError> {
+            crate::validate_configs(configs)
+        }
+
+        fn _validate_contribution(&self, value: Balance) -> Result<(), GameError> {
+            // NOTE: the issue here is since this game is publicgood, some amount has to be
+            // contributed to the pot. So, we need to check if the player has contributed
+            // that amount. But we also don't want to reveal the contribution :)
+            // one way is to have the payable amount always be fixed and be maxed out
+            // while the hashed commitment contains the real amount to be contributed.
             ensure!(
-                current_round.player_reveals.len() == self.players.len(),
-                GameError::NotAllPlayersRevealed
+                value >= Balance::from(self.game.configs.max_round_contribution.unwrap_or(0)),
+                GameError::InvalidRoundContribution
             );
-            // ensure round state is still valid
+            Ok(())
+        }
+
+        fn _on_reveal(&mut self, player: AccountId, reveal: (u128, u128)) -> Result<(), GameError> {
+            let max_round_contribution = self.game.configs.max_round_contribution.unwrap();
+            // ensure the revealed contribution is within the amount deposited
             ensure!(
-                current_round.status != RoundStatus::Ended,
-                GameError::InvalidGameState
+                reveal.0 <= max_round_contribution,
+                GameError::InvalidRoundContribution
             );
 
-            // mark round as ended
-            current_round.status = RoundStatus::Ended;
-            // get winners
-            let winners = PublicGood::get_winners(&current_round, &self.configs, &self.players)
-                .map_err(|err| err)?;
-
-            // issue winner rewards
-            winners.iter().for_each(|(player, reward)| match reward {
-                Some(reward) => {
-                    let _ = Self::env()
-                        .transfer(*player, *reward)
-                        .map_err(|_| GameError::FailedToIssueWinnerRewards);
-                }
-                None => (),
-            });
+            // credit the partial contribution back to the player
+            // this is done because all players contribute the max amount when making a commitment
+            // to avoid information leakage
+            self._credit(player, max_round_contribution - reveal.0);
 
-            self.env().emit_event(RoundCompleted {
-                game_address: Self::env().account_id(),
-                round_id: self.current_round.as_ref().unwrap().id,
-                winners,
-            });
+            Ok(())
+        }
 
-            // TODO: handle checking players who haven't played
+        /// Players who committed but did not reveal have already been dealt with by the
+        /// game's `NonRevealPolicy`.
+        ///
+        /// With `post_round_actions`, the payouts are held in an `ActionStage` and credited by
+        /// `complete_actions` instead.
+        fn _settle_round(&mut self, round: &GameRound) -> Result<RoundPayouts, GameError> {
+            let winners = PublicGood::get_winners(round, &self.game.configs, &self.game.players)?;
+            let payouts: Vec<(AccountId, u128)> = winners
+                .into_iter()
+                .filter_map(|(player, reward)| reward.map(|reward| (player, reward)))
+                .collect();
 
-            // check if there's a next round or game ended
-            if self.configs.max_rounds.unwrap_or(999) < self.next_round_id.into() {
-                self.status = GameStatus::Ended;
-                Self::env().emit_event(GameEnded {
-                    game_address: Self::env().account_id(),
-                });
-            } else {
-                self.current_round = Some(GameRound {
-                    id: self.next_round_id,
-                    status: RoundStatus::Ready,
-                    player_commits: Vec::new(),
-                    player_reveals: Vec::new(),
-                    player_contributions: Vec::new(),
-                    total_contribution: 0,
-                    total_reward: 0,
-                });
-                self.next_round_id += 1;
+            if !self.game.configs.post_round_actions {
+                return Ok(RoundPayouts::winnings(payouts));
             }
 
-            // TODO: emit event (new round started)
+            // hold the payouts until the players have assigned their points
+            ensure!(self.action_stage.is_none(), GameError::ActionStageOngoing);
+            self.action_stage = Some(ActionStage::new(
+                round.id,
+                payouts,
+                self.env().block_number(),
+                self.game.configs.round_timeout,
+            ));
 
-            Ok(())
+            Ok(RoundPayouts::default())
         }
 
-        #[ink(message, payable)]
-        fn end_game(&mut self) -> Result<(), GameError> {
-            // ensure the game is in ended state
-            ensure!(
-                self.status == GameStatus::Ended,
-                GameError::InvalidGameState
-            );
-            // terminate the contract and send remaining balance to the contract's creator
-            Self::env().terminate_contract(self.created_by);
+        fn _emit_player_joined_event(&self, player: AccountId) {
+            self.env().emit_event(PlayerJoined {
+                game_address: self.env().account_id(),
+                player,
+            });
         }
-    }
 
-    /// An implementation of the `Utils` trait containing internal helper functions
+        fn _emit_game_started_event(&self) {
+            self.env().emit_event(GameStarted {
+                game_address: self.env().account_id(),
+            });
+        }
+
+        fn _emit_player_committed_event(&self, player: AccountId, commitment: Hash) {
+            self.env().emit_event(RoundCommitPlayed {
+                game_address: self.env().account_id(),
+                player,
+                commitment,
+            });
+        }
+
+        fn _emit_all_players_committed_event(&self, round: &GameRound) {
+            self.env().emit_event(AllPlayersCommitted {
+                game_address: self.env().account_id(),
+                round_id: round.id,
+            });
+        }
+
+        fn _emit_player_revealed_event(&self, player: AccountId, reveal: (u128, u128)) {
+            self.env().emit_event(RoundCommitRevealed {
+                game_address: self.env().account_id(),
+                player,
+                reveal: Some(reveal),
+            });
+        }
+
+        fn _emit_round_completed_event(&self, round: &GameRound, payouts: &[(AccountId, u128)]) {
+            self.env().emit_event(RoundCompleted {
+                game_address: self.env().account_id(),
+                round_id: round.id,
+                winners: payouts
+                    .iter()
+                    .map(|(player, reward)| (*player, Some(*reward)))
+                    .collect(),
+                protocol_fee: round.protocol_fee,
+            });
+        }
+
+        fn _emit_round_force_closed_event(&self, round_id: u8, closed_by: AccountId) {
+            self.env().emit_event(RoundForceClosed {
+                game_address: self.env().account_id(),
+                round_id,
+                admin_id: closed_by,
+            });
+        }
+
+        fn _emit_player_left_event(&self, player: AccountId) {
+            self.env().emit_event(PlayerLeft {
+                game_address: self.env().account_id(),
+                player,
+            });
+        }
+
+        fn _emit_game_cancelled_event(&self) {
+            self.env().emit_event(GameCancelled {
+                game_address: self.env().account_id(),
+            });
+        }
+
+        fn _emit_game_settled_event(&self, transfers: &[(AccountId, u128)]) {
+            self.env().emit_event(GameSettled {
+                game_address: self.env().account_id(),
+                transfers: transfers.to_vec(),
+            });
+        }
+
+        fn _emit_router_notification_failed_event(&self, router: AccountId, callback: [u8; 4]) {
+            self.env().emit_event(RouterNotificationFailed {
+                game_address: self.env().account_id(),
+                router,
+                callback,
+            });
+        }
+
+        fn _emit_claimed_event(&self, player: AccountId, amount: u128) {
+            self.env().emit_event(BalanceClaimed {
+                game_address: self.env().account_id(),
+                player,
+                amount,
+            });
+        }
+
+        fn _emit_player_penalized_event(&self, round_id: u8, player: AccountId, penalty: u128) {
+            self.env().emit_event(PlayerPenalized {
+                game_address: self.env().account_id(),
+                player,
+                round_id,
+                penalty,
+            });
+        }
+
+        fn _emit_game_ended_event(&self) {
+            self.env().emit_event(GameEnded {
+                game_address: self.env().account_id(),
+            });
+        }
+    }
+
+    /// An implementation of the `Utils` trait containing internal helper functions
     impl Utils for PublicGood {
+        /// Pays every player of the game an equal share of the public good produced by the
+        /// round's pot, see `PayoffRule`. The endowment kept by each player is credited back
+        /// when they reveal.
+        ///
+        /// The pot holds the revealed contributions and whatever the `NonRevealPolicy` forfeited
+        /// from the non-revealers. Like the players who didn't contribute, the non-revealers
+        /// still share in the public good, unless the policy excluded them from the game.
         fn get_winners(
             round: &GameRound,
             configs: &GameConfigs,
-            _players: &Vec<AccountId>,
+            players: &Vec<AccountId>,
         ) -> Result<Vec<(AccountId, Option<u128>)>, GameError> {
             if round.status != RoundStatus::Ended {
                 return Err(GameError::RoundNotEnded);
             }
 
-            // for each player that played, apply the multiplier to their contribution
-            let winners: Vec<(AccountId, Option<u128>)> = round
+            let mut contributions: Vec<u128> = round
                 .player_reveals
                 .iter()
-                .map(|&(account_id, play)| {
-                    (
-                        account_id,
-                        Some(
-                            (play.0 * configs.round_reward_multiplier.unwrap().abs() as u128) / 10,
-                        ),
-                    )
-                })
+                .map(|(_, reveal)| reveal.0)
                 .collect();
-
-            Ok(winners)
+            // what's left of the deposits once the revealers' are taken out was forfeited
+            let revealers_deposits = round
+                .player_contributions
+                .iter()
+                .filter(|(player, _)| round.player_reveals.iter().any(|(p, _)| p == player))
+                .fold(0u128, |total, (_, deposit)| total.saturating_add(*deposit));
+            let forfeited = round.total_contribution.saturating_sub(revealers_deposits);
+            if forfeited > 0 {
+                contributions.push(forfeited);
+            }
+            let multiplier = configs
+                .round_reward_multiplier
+                .ok_or(GameError::FailedToGetWinners)?
+                .unsigned_abs() as u128;
+
+            Ok(configs
+                .payoff_formula
+                .payoffs(&contributions, players, multiplier)
+                .into_iter()
+                .map(|(player, payoff)| (player, Some(payoff)))
+                .collect())
         }
     }
 
@@ -520,26 +868,14 @@ pub mod public_good {
         #[ink(message, payable)]
         #[modifiers(only_role(CREATOR))]
         fn add_player_to_game(&mut self, player: AccountId) -> Result<u8, GameError> {
-            // ensure that there's more room in the game
-            ensure!(
-                self.players.len() < self.configs.max_players as usize,
-                GameError::MaxPlayersReached
-            );
             // add player to state
-            self.players.push(player);
-            // any paid amount should be transferred to that particular player from the contract
+            let players_count = self._add_player(player)?;
+            // any paid amount is held as the player's deposit, to be refunded when the game ends
             let value = Self::env().transferred_value();
             if value > 0 {
-                Self::env()
-                    .transfer(player, Self::env().transferred_value())
-                    .map_err(|_| GameError::FailedToAddPlayer)?;
+                self.game.join_fees.push((player, value));
             }
-            // emit PlayerJoined event
-            Self::env().emit_event(PlayerJoined {
-                game_address: Self::env().account_id(),
-                player,
-            });
-            Ok(self.players.len() as u8)
+            Ok(players_count)
         }
 
         #[ink(message)]
@@ -549,86 +885,69 @@ pub mod public_good {
             as_player: AccountId,
             commitment: Hash,
         ) -> Result<(), GameError> {
-            // TODO: refactor this logic into something re-usable for both admin and player
-
-            // ensure valid game state
-            ensure!(
-                self.status == GameStatus::OnGoing,
-                GameError::GameNotStarted
-            );
-            // ensure current round exists
-            ensure!(self.current_round.is_some(), GameError::NoCurrentRound);
-
-            let value = Self::env().transferred_value();
-            // NOTE: the issue of contribution amount privacy is discussed in the `play_round` method implementation.
+            ensure!(self.action_stage.is_none(), GameError::ActionStageOngoing);
+            // NOTE: the issue of contribution amount privacy is discussed in `_validate_contribution`.
             // It's the reason we require the max_round_contribution amount here
-            ensure!(
-                value >= Balance::from(self.configs.max_round_contribution.unwrap_or(0)),
-                GameError::InvalidRoundContribution
-            );
-
-            let caller = as_player.clone();
-            let current_round = self.current_round.as_mut().unwrap();
-
-            // ensure that the player hasn't already made a commitment
-            ensure!(
-                current_round
-                    .player_commits
-                    .iter()
-                    .find(|(player, _)| player == &caller)
-                    .is_none(),
-                GameError::PlayerAlreadyCommitted
-            );
-
-            // store the commit
-            current_round
-                .player_commits
-                .push((as_player.clone(), commitment));
-
-            // keep track of round contribution(s)
-            current_round
-                .player_contributions
-                .push((as_player.clone(), value));
-
-            current_round.total_contribution += value;
-
-            // check if all players have committed
-            if current_round.player_commits.len() == self.players.len() {
-                Self::env().emit_event(AllPlayersCommitted {
-                    game_address: Self::env().account_id(),
-                    round_id: current_round.id.clone(),
-                });
-            }
-
-            Self::env().emit_event(RoundCommitPlayed {
-                game_address: Self::env().account_id(),
-                player: caller,
-                commitment,
-            });
-            Ok(())
+            self._commit(as_player, commitment, Self::env().transferred_value())
         }
 
         #[ink(message)]
         #[modifiers(only_role(CREATOR))]
-        fn reveal_round_as_player(&mut self) -> Result<(), GameError> {
-            todo!("implement")
+        fn reveal_round_as_player(
+            &mut self,
+            as_player: AccountId,
+            reveal: (u128, u128),
+        ) -> Result<(), GameError> {
+            self._reveal(as_player, reveal)
         }
 
         #[ink(message)]
         #[modifiers(only_role(CREATOR))]
         fn force_complete_round(&mut self) -> Result<(), GameError> {
-            todo!("implement")
+            // close the round with whichever reveals exist, non-revealers are penalized
+            self._force_close_round()
         }
 
         #[ink(message)]
         #[modifiers(only_role(CREATOR))]
         fn force_end_game(&mut self) -> Result<(), GameError> {
-            todo!("implement")
+            // close the current round if it's still open, a round nobody played (e.g. the one
+            // following an action stage) has nothing to settle
+            if self.game.status == GameStatus::OnGoing {
+                let round = self.game.current_round.as_ref().unwrap();
+                if round.status != RoundStatus::Ended && !round.player_commits.is_empty() {
+                    self._force_close_round()?;
+                }
+            }
+            // release the payouts held by the stage following the last round
+            if self.action_stage.is_some() {
+                self.complete_actions()?;
+            }
+
+            if self.game.status != GameStatus::Ended {
+                self.game.status = GameStatus::Ended;
+                self._emit_game_ended_event();
+                self._notify_game_ended();
+            }
+
+            // refund the deposits, the contract is only terminated once every player has
+            // claimed their balance, `end_game` can be used otherwise
+            self._settle_game();
+            if self._is_settled() {
+                self._terminate_game()?;
+            }
+
+            Ok(())
         }
 
         #[ink(message, payable)]
         fn fund_contract(&mut self) -> Result<(), GameError> {
             // ensure!(self.env().transferred_value() > 0);
+            // keep track of the endowment, what's left of it is returned when the game ends
+            let value = self.env().transferred_value();
+            if value > 0 {
+                self.game.endowments.push((self.env().caller(), value));
+            }
 
             Ok(())
         }
@@ -638,6 +957,9 @@ pub mod public_good {
     #[cfg(test)]
     mod tests {
         use super::*;
+        use game_theory::logics::traits::types::{
+            ActionRatios, ConfigError, NonRevealPolicy, PayoffFormula, TiePolicy,
+        };
         use ink::env::test::EmittedEvent;
         use openbrush::traits::Balance;
 
@@ -676,23 +998,65 @@ pub mod public_good {
             }
 
             if configs.play_commits {
-                let mut commitment = <Blake2x256 as HashOutput>::Type::default();
-                let data = [100u128.to_le_bytes(), 144u128.to_le_bytes()].concat();
-                ink::env::hash_bytes::<Blake2x256>(&data, &mut commitment);
-
-                set_value(game_public_good.configs.max_round_contribution.unwrap());
+                set_value(max_contribution(&game_public_good));
 
                 set_caller(accounts.alice);
-                assert!(game_public_good.play_round(commitment.into()).is_ok());
+                assert!(game_public_good
+                    .play_round(commitment_of(&game_public_good, accounts.alice))
+                    .is_ok());
                 set_caller(accounts.bob);
-                assert!(game_public_good.play_round(commitment.into()).is_ok());
+                assert!(game_public_good
+                    .play_round(commitment_of(&game_public_good, accounts.bob))
+                    .is_ok());
                 set_caller(accounts.charlie);
-                assert!(game_public_good.play_round(commitment.into()).is_ok());
+                assert!(game_public_good
+                    .play_round(commitment_of(&game_public_good, accounts.charlie))
+                    .is_ok());
+            }
+
+            game_public_good
+        }
+
+        fn commitment_of(game: &PublicGood, player: AccountId) -> Hash {
+            game.hash_commitment(player, 100, 144).unwrap()
+        }
+
+        fn assign_points(
+            game: &mut PublicGood,
+            player: AccountId,
+            actions: Vec<PointsAssignment>,
+        ) -> Result<(), GameError> {
+            let commitment = game
+                .hash_actions_commitment(player, actions.clone(), 7)
+                .unwrap();
+            set_caller(player);
+            game.commit_actions(commitment)?;
+            game.reveal_actions(actions, 7)
+        }
+
+        /// Completes a round in which every player contributed 100, followed by an action stage.
+        fn setup_action_stage() -> PublicGood {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: true,
+            });
+            game_public_good.game.configs.post_round_actions = true;
+
+            for player in [accounts.alice, accounts.bob, accounts.charlie] {
+                set_caller(player);
+                assert!(game_public_good.reveal_round((100, 144)).is_ok());
             }
+            assert!(game_public_good.complete_round().is_ok());
 
             game_public_good
         }
 
+        fn max_contribution(game: &PublicGood) -> Balance {
+            game.game.configs.max_round_contribution.unwrap()
+        }
+
         fn set_caller(account: AccountId) -> () {
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(account);
         }
@@ -705,7 +1069,7 @@ pub mod public_good {
         #[ink::test]
         fn default_works() {
             let game_public_good = PublicGood::default();
-            assert_eq!(game_public_good.players, vec![]);
+            assert_eq!(game_public_good.game.players, vec![]);
             assert_eq!(game_public_good.get_current_round(), None);
         }
 
@@ -722,12 +1086,46 @@ pub mod public_good {
                 round_timeout: None,
                 max_rounds: None,
                 join_fee: None,
+                join_deadline: None,
                 is_rounds_based: false,
-            });
-            assert_eq!(game_public_good.players, vec![]);
+                non_reveal_policy: NonRevealPolicy::ForfeitToPot,
+                tie_policy: TiePolicy::Replay,
+                elimination: false,
+                move_count: None,
+                router: None,
+                creator: None,
+                ratings: None,
+                protocol_fee_bps: 0,
+                payoff_formula: PayoffFormula::Linear,
+                action_ratios: ActionRatios::default(),
+            })
+            .unwrap();
+            assert_eq!(game_public_good.game.players, vec![]);
             assert_eq!(game_public_good.get_current_round(), None);
         }
 
+        /// Invalid configs are rejected with the reason.
+        #[ink::test]
+        fn new_rejects_invalid_configs() {
+            let mut configs = PublicGood::default().game.configs;
+            configs.min_round_contribution = Some(2_000);
+            assert_eq!(
+                PublicGood::new(configs.clone()).err(),
+                Some(GameError::InvalidConfig(
+                    ConfigError::MinContributionAboveMax
+                ))
+            );
+
+            configs.min_round_contribution = Some(100);
+            configs.round_reward_multiplier = None;
+            assert_eq!(
+                PublicGood::new(configs).err(),
+                Some(GameError::InvalidConfig(
+                    ConfigError::RewardMultiplierMissing
+                ))
+            );
+        }
+
         /// A new player can join the game.
         #[ink::test]
         fn player_can_join() {
@@ -815,19 +1213,18 @@ pub mod public_good {
         /// A player can play a round.
         #[ink::test]
         fn player_can_play_round() {
+            let accounts = get_accounts();
             let mut game_public_good = setup_game(SetupTestGame {
                 join_game: true,
                 start_game: true,
                 play_commits: false,
             });
 
-            let mut commitment = <Blake2x256 as HashOutput>::Type::default();
-            let data = [100u128.to_le_bytes(), 144u128.to_le_bytes()].concat();
-            ink::env::hash_bytes::<Blake2x256>(&data, &mut commitment);
+            let commitment = commitment_of(&game_public_good, accounts.charlie);
 
             // can play a round
-            set_value(game_public_good.configs.max_round_contribution.unwrap());
-            match game_public_good.play_round(commitment.into()) {
+            set_value(max_contribution(&game_public_good));
+            match game_public_good.play_round(commitment) {
                 Err(error) => {
                     println!("{:?}", error);
                     assert!(false);
@@ -837,34 +1234,64 @@ pub mod public_good {
 
             // round commit is stored
             let commits = game_public_good
+                .game
                 .current_round
                 .as_ref()
                 .unwrap()
                 .player_commits
                 .clone();
             assert_eq!(commits.len(), 1);
-            assert_eq!(commits.first().unwrap().1, commitment.into());
+            assert_eq!(commits.first().unwrap().1, commitment);
         }
 
         /// A player cannot play a round twice.
         #[ink::test]
         fn player_cannot_play_twice() {
+            let accounts = get_accounts();
             let mut game_public_good = setup_game(SetupTestGame {
                 join_game: true,
                 start_game: true,
                 play_commits: false,
             });
 
-            let mut commitment = <Blake2x256 as HashOutput>::Type::default();
-            let data = [100u128.to_le_bytes(), 144u128.to_le_bytes()].concat();
-            ink::env::hash_bytes::<Blake2x256>(&data, &mut commitment);
+            let commitment = commitment_of(&game_public_good, accounts.charlie);
 
             // can play a round
-            set_value(game_public_good.configs.max_round_contribution.unwrap());
-            assert!(game_public_good.play_round(commitment.into()).is_ok());
+            set_value(max_contribution(&game_public_good));
+            assert!(game_public_good.play_round(commitment).is_ok());
 
             // cannot play again for the same round
-            assert!(game_public_good.play_round(commitment.into()).is_err());
+            assert!(game_public_good.play_round(commitment).is_err());
+        }
+
+        /// A commitment is bound to the player who made it.
+        #[ink::test]
+        fn commitment_cannot_be_copied() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: false,
+            });
+
+            // the same reveal hashes differently for each player
+            assert_ne!(
+                commitment_of(&game_public_good, accounts.alice),
+                commitment_of(&game_public_good, accounts.bob)
+            );
+
+            let commitment = commitment_of(&game_public_good, accounts.alice);
+            set_value(max_contribution(&game_public_good));
+
+            set_caller(accounts.alice);
+            assert!(game_public_good.play_round(commitment).is_ok());
+
+            // bob can't submit alice's commitment
+            set_caller(accounts.bob);
+            assert_eq!(
+                game_public_good.play_round(commitment).err(),
+                Some(GameError::DuplicateCommitment)
+            );
         }
 
         /// All players can play round.
@@ -877,21 +1304,23 @@ pub mod public_good {
                 play_commits: false,
             });
 
-            let mut commitment = <Blake2x256 as HashOutput>::Type::default();
-            let data = [100u128.to_le_bytes(), 144u128.to_le_bytes()].concat();
-            ink::env::hash_bytes::<Blake2x256>(&data, &mut commitment);
-
             // can play a round
-            set_value(game_public_good.configs.max_round_contribution.unwrap());
+            set_value(max_contribution(&game_public_good));
 
             set_caller(accounts.alice);
-            assert!(game_public_good.play_round(commitment.into()).is_ok());
+            assert!(game_public_good
+                .play_round(commitment_of(&game_public_good, accounts.alice))
+                .is_ok());
 
             set_caller(accounts.charlie);
-            assert!(game_public_good.play_round(commitment.into()).is_ok());
+            assert!(game_public_good
+                .play_round(commitment_of(&game_public_good, accounts.charlie))
+                .is_ok());
 
             set_caller(accounts.bob);
-            assert!(game_public_good.play_round(commitment.into()).is_ok());
+            assert!(game_public_good
+                .play_round(commitment_of(&game_public_good, accounts.bob))
+                .is_ok());
         }
 
         /// Each player can play the round then reveal.
@@ -914,6 +1343,7 @@ pub mod public_good {
             // check that all reveals are stored in state
             assert_eq!(
                 game_public_good
+                    .game
                     .current_round
                     .as_ref()
                     .unwrap()
@@ -971,7 +1401,7 @@ pub mod public_good {
                 }
                 Ok(_) => {
                     // check that the round ID has been incremented
-                    assert_eq!(game_public_good.next_round_id, 3);
+                    assert_eq!(game_public_good.game.next_round_id, 3);
                     // check that the relevant round completion event is emitted
                     let events: Vec<EmittedEvent> =
                         ink::env::test::recorded_events().collect::<Vec<_>>();
@@ -1009,22 +1439,20 @@ pub mod public_good {
                 play_commits: false,
             });
 
-            let mut commitment = <Blake2x256 as HashOutput>::Type::default();
-            let data = [100u128.to_le_bytes(), 144u128.to_le_bytes()].concat();
-            ink::env::hash_bytes::<Blake2x256>(&data, &mut commitment);
-
-            let mut commitment2 = <Blake2x256 as HashOutput>::Type::default();
-            let data2 = [100u128.to_le_bytes(), 144u128.to_le_bytes()].concat();
-            ink::env::hash_bytes::<Blake2x256>(&data2, &mut commitment2);
-
-            set_value(game_public_good.configs.max_round_contribution.unwrap());
+            set_value(max_contribution(&game_public_good));
 
             set_caller(accounts.alice);
-            assert!(game_public_good.play_round(commitment.into()).is_ok());
+            assert!(game_public_good
+                .play_round(commitment_of(&game_public_good, accounts.alice))
+                .is_ok());
             set_caller(accounts.bob);
-            assert!(game_public_good.play_round(commitment.into()).is_ok());
+            assert!(game_public_good
+                .play_round(commitment_of(&game_public_good, accounts.bob))
+                .is_ok());
             set_caller(accounts.charlie);
-            assert!(game_public_good.play_round(commitment2.into()).is_ok());
+            assert!(game_public_good
+                .play_round(commitment_of(&game_public_good, accounts.charlie))
+                .is_ok());
 
             // do the reveal step for each player
             set_caller(accounts.alice);
@@ -1050,8 +1478,7 @@ pub mod public_good {
 
             let contribution_amount = 100;
             let alice_balance = get_balance(accounts.alice);
-            let expected_refund =
-                game_public_good.configs.max_round_contribution.unwrap() - contribution_amount;
+            let expected_refund = max_contribution(&game_public_good) - contribution_amount;
 
             // do the reveal step for each player
             set_caller(accounts.alice);
@@ -1059,8 +1486,763 @@ pub mod public_good {
                 .reveal_round((contribution_amount, 144))
                 .is_ok());
 
-            // attempt to complete the round
+            // the refund is credited and can be claimed
+            assert_eq!(
+                game_public_good.get_claimable(accounts.alice),
+                expected_refund
+            );
+            assert_eq!(game_public_good.claim(), Ok(expected_refund));
             assert_eq!(get_balance(accounts.alice), alice_balance + expected_refund);
+            assert_eq!(game_public_good.get_claimable(accounts.alice), 0);
+            assert_eq!(game_public_good.claim(), Err(GameError::NothingToClaim));
+        }
+
+        /// The game can only be ended once every player has claimed their balance.
+        #[ink::test]
+        fn end_game_requires_settled_balances() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: true,
+            });
+
+            set_caller(accounts.alice);
+            assert!(game_public_good.reveal_round((100, 144)).is_ok());
+            assert!(game_public_good.force_end_game().is_ok());
+            assert_eq!(game_public_good.game.status, GameStatus::Ended);
+            assert_eq!(
+                game_public_good.end_game(),
+                Err(GameError::BalancesNotSettled)
+            );
+
+            // alice's refund and reward are waiting to be claimed
+            assert!(game_public_good.get_claimable(accounts.alice) > 0);
+            assert!(game_public_good.claim().is_ok());
+        }
+
+        /// A player can leave before the game starts and get their join fee back.
+        #[ink::test]
+        fn player_can_leave_before_start() {
+            let accounts = get_accounts();
+            let mut game_public_good = PublicGood::default();
+            game_public_good.game.configs.join_fee = Some(10);
+
+            set_caller(accounts.alice);
+            set_value(10);
+            assert!(game_public_good.join(accounts.alice).is_ok());
+            set_caller(accounts.bob);
+            assert!(game_public_good.join(accounts.bob).is_ok());
+
+            assert_eq!(game_public_good.leave(), Ok(1));
+            assert_eq!(game_public_good.game.players, vec![accounts.alice]);
+            assert_eq!(game_public_good.get_claimable(accounts.bob), 10);
+            assert_eq!(game_public_good.leave(), Err(GameError::PlayerNotInGame));
+        }
+
+        /// Anyone can cancel the game once the join deadline has passed.
+        #[ink::test]
+        fn lobby_can_be_cancelled_after_join_deadline() {
+            let accounts = get_accounts();
+            let mut game_public_good = PublicGood::default();
+            game_public_good.game.configs.join_fee = Some(10);
+            game_public_good.game.configs.join_deadline = Some(1);
+
+            set_caller(accounts.alice);
+            set_value(10);
+            assert!(game_public_good.join(accounts.alice).is_ok());
+            assert_eq!(
+                game_public_good.cancel_game(),
+                Err(GameError::JoinDeadlineNotPassed)
+            );
+
+            for _ in 0..2 {
+                ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            }
+            set_caller(accounts.bob);
+            assert_eq!(
+                game_public_good.join(accounts.bob),
+                Err(GameError::JoinDeadlinePassed)
+            );
+
+            assert!(game_public_good.cancel_game().is_ok());
+            assert_eq!(game_public_good.get_status(), GameStatus::Cancelled);
+            assert_eq!(game_public_good.get_claimable(accounts.alice), 10);
+        }
+
+        /// A game which reached `min_players` can't be cancelled once the join deadline has passed.
+        #[ink::test]
+        fn lobby_with_enough_players_cannot_be_cancelled() {
+            let accounts = get_accounts();
+            let mut game_public_good = PublicGood::default();
+            game_public_good.game.configs.join_deadline = Some(1);
+
+            for player in [accounts.alice, accounts.bob] {
+                set_caller(player);
+                assert!(game_public_good.join(player).is_ok());
+            }
+            for _ in 0..2 {
+                ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            }
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                game_public_good.cancel_game(),
+                Err(GameError::EnoughPlayersJoined)
+            );
+            assert_eq!(game_public_good.get_status(), GameStatus::Ready);
+        }
+
+        /// Completed rounds are archived and can be queried.
+        #[ink::test]
+        fn completed_rounds_are_archived() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: true,
+            });
+            assert_eq!(game_public_good.get_rounds_count(), 0);
+            assert_eq!(game_public_good.get_round(1), None);
+
+            for player in [accounts.alice, accounts.bob, accounts.charlie] {
+                set_caller(player);
+                assert!(game_public_good.reveal_round((100, 144)).is_ok());
+            }
+            assert!(game_public_good.complete_round().is_ok());
+
+            assert_eq!(game_public_good.get_rounds_count(), 1);
+            let round = game_public_good.get_round(1).unwrap();
+            assert_eq!(round.status, RoundStatus::Ended);
+            assert_eq!(round.player_reveals.len(), 3);
+            assert_eq!(game_public_good.get_rounds(1, 10), vec![round]);
+            assert_eq!(game_public_good.get_rounds(2, 10), vec![]);
+        }
+
+        /// The pot is multiplied and split equally between every player, on top of the
+        /// endowment each player kept.
+        #[ink::test]
+        fn pot_is_multiplied_and_shared_by_the_group() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: false,
+            });
+
+            // charlie free rides, contributing nothing
+            let contributions = [
+                (accounts.alice, 400),
+                (accounts.bob, 200),
+                (accounts.charlie, 0),
+            ];
+            set_value(max_contribution(&game_public_good));
+            for (player, contribution) in contributions {
+                let commitment = game_public_good
+                    .hash_commitment(player, contribution, 144)
+                    .unwrap();
+                set_caller(player);
+                assert!(game_public_good.play_round(commitment).is_ok());
+            }
+            for (player, contribution) in contributions {
+                set_caller(player);
+                assert!(game_public_good.reveal_round((contribution, 144)).is_ok());
+            }
+            assert!(game_public_good.complete_round().is_ok());
+
+            // the pot of 600 is doubled and split in 3
+            let share = 600 * 2 / 3;
+            assert_eq!(game_public_good.get_claimable(accounts.alice), 600 + share);
+            assert_eq!(game_public_good.get_claimable(accounts.bob), 800 + share);
+            assert_eq!(
+                game_public_good.get_claimable(accounts.charlie),
+                1_000 + share
+            );
+        }
+
+        /// The next round can't be played during the action stage, so its deadlines only start
+        /// once the stage is completed.
+        #[ink::test]
+        fn next_round_deadlines_start_after_the_action_stage() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: true,
+            });
+            game_public_good.game.configs.post_round_actions = true;
+            game_public_good.game.configs.round_timeout = Some(2);
+            for player in [accounts.alice, accounts.bob, accounts.charlie] {
+                set_caller(player);
+                assert!(game_public_good.reveal_round((100, 144)).is_ok());
+            }
+            assert!(game_public_good.complete_round().is_ok());
+
+            // the stage outlasts the deadlines the next round was opened with
+            for _ in 0..5 {
+                ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            }
+            let block = ink::env::block_number::<ink::env::DefaultEnvironment>();
+            assert!(game_public_good
+                .get_current_round()
+                .unwrap()
+                .is_expired(block));
+            assert!(game_public_good.complete_actions().is_ok());
+
+            let round = game_public_good.get_current_round().unwrap();
+            assert_eq!(round.start_block, block);
+            assert_eq!(round.commit_deadline, Some(block + 2));
+            assert!(!round.is_expired(block));
+
+            set_caller(accounts.alice);
+            set_value(max_contribution(&game_public_good));
+            let commitment = commitment_of(&game_public_good, accounts.alice);
+            assert_eq!(game_public_good.play_round(commitment), Ok(()));
+        }
+
+        /// With post round actions, the payouts of a round are adjusted by the punishment and
+        /// reward points the players assign each other.
+        #[ink::test]
+        fn points_adjust_the_payouts_of_the_round() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_action_stage();
+
+            // only the kept endowments are credited, the payouts are held
+            assert_eq!(game_public_good.get_claimable(accounts.bob), 900);
+            assert_eq!(
+                game_public_good.get_action_stage().unwrap().payouts,
+                vec![
+                    (accounts.alice, 200),
+                    (accounts.bob, 200),
+                    (accounts.charlie, 200)
+                ]
+            );
+            set_caller(accounts.alice);
+            set_value(max_contribution(&game_public_good));
+            let commitment = commitment_of(&game_public_good, accounts.alice);
+            assert_eq!(
+                game_public_good.play_round(commitment),
+                Err(GameError::ActionStageOngoing)
+            );
+
+            // alice punishes charlie and rewards bob
+            assert_eq!(
+                assign_points(
+                    &mut game_public_good,
+                    accounts.alice,
+                    vec![
+                        PointsAssignment {
+                            to: accounts.charlie,
+                            punishment: 10,
+                            reward: 0,
+                        },
+                        PointsAssignment {
+                            to: accounts.bob,
+                            punishment: 0,
+                            reward: 5,
+                        },
+                    ]
+                ),
+                Ok(())
+            );
+            assert_eq!(
+                assign_points(&mut game_public_good, accounts.bob, vec![]),
+                Ok(())
+            );
+            assert_eq!(
+                assign_points(&mut game_public_good, accounts.charlie, vec![]),
+                Ok(())
+            );
+            assert!(game_public_good.complete_actions().is_ok());
+
+            assert_eq!(game_public_good.get_action_stage(), None);
+            assert_eq!(game_public_good.get_claimable(accounts.alice), 900 + 185);
+            assert_eq!(game_public_good.get_claimable(accounts.bob), 900 + 205);
+            assert_eq!(game_public_good.get_claimable(accounts.charlie), 900 + 170);
+
+            let assigned = ink::env::test::recorded_events()
+                .filter(|e| {
+                    matches!(
+                        <Event as scale::Decode>::decode(&mut &e.data[..]),
+                        Ok(Event::PointsAssigned(_))
+                    )
+                })
+                .count();
+            assert_eq!(assigned, 2);
+
+            // the next round can be played
+            set_caller(accounts.alice);
+            assert!(game_public_good.play_round(commitment).is_ok());
+        }
+
+        /// Points can only be assigned to the other members of the group, within the payout
+        /// of the player, and only the creator can complete the stage early.
+        #[ink::test]
+        fn points_must_be_valid() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_action_stage();
+            let punish = |to, punishment| PointsAssignment {
+                to,
+                punishment,
+                reward: 0,
+            };
+
+            set_caller(accounts.django);
+            assert_eq!(
+                game_public_good.commit_actions(Hash::from([1; 32])),
+                Err(GameError::PlayerNotInGame)
+            );
+            assert_eq!(
+                assign_points(
+                    &mut game_public_good,
+                    accounts.alice,
+                    vec![punish(accounts.django, 1)]
+                ),
+                Err(GameError::InvalidChoice)
+            );
+            assert_eq!(
+                assign_points(
+                    &mut game_public_good,
+                    accounts.bob,
+                    vec![punish(accounts.bob, 1)]
+                ),
+                Err(GameError::InvalidChoice)
+            );
+            assert_eq!(
+                assign_points(
+                    &mut game_public_good,
+                    accounts.charlie,
+                    vec![punish(accounts.alice, 201)]
+                ),
+                Err(GameError::BalanceNotEnough)
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                game_public_good.complete_actions(),
+                Err(GameError::NotAllPlayersRevealed)
+            );
+            assert_eq!(
+                game_public_good.end_game(),
+                Err(GameError::ActionStageOngoing)
+            );
+
+            // nobody assigned points, the payouts are released as is
+            set_caller(accounts.alice);
+            assert!(game_public_good.complete_actions().is_ok());
+            assert_eq!(game_public_good.get_claimable(accounts.charlie), 1_100);
+            assert_eq!(
+                game_public_good.complete_actions(),
+                Err(GameError::NoActionStage)
+            );
+        }
+
+        /// Forcing the end of the game during an action stage releases its payouts, the next
+        /// round wasn't played and has nothing to settle.
+        #[ink::test]
+        fn force_end_game_completes_the_action_stage() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_action_stage();
+
+            set_caller(accounts.alice);
+            assert_eq!(game_public_good.force_end_game(), Ok(()));
+            assert_eq!(game_public_good.get_status(), GameStatus::Ended);
+            assert_eq!(game_public_good.get_action_stage(), None);
+            assert_eq!(game_public_good.get_claimable(accounts.charlie), 1_100);
+            assert_eq!(
+                game_public_good.end_game(),
+                Err(GameError::BalancesNotSettled)
+            );
+        }
+
+        /// The payoff formula can be swapped for a threshold or a saturating one.
+        #[ink::test]
+        fn payoff_formulas() {
+            let group = [get_accounts().alice, get_accounts().bob];
+
+            assert_eq!(PayoffFormula::Linear.produce(300, 20), 600);
+            assert_eq!(
+                PayoffFormula::Linear.payoffs(&[100, 201], &group, 15),
+                vec![(group[0], 225), (group[1], 225)]
+            );
+            assert_eq!(PayoffFormula::Linear.payoffs(&[100], &[], 20), vec![]);
+
+            let threshold = PayoffFormula::Threshold { threshold: 300 };
+            assert_eq!(threshold.produce(299, 20), 0);
+            assert_eq!(threshold.produce(300, 20), 600);
+
+            let saturating = PayoffFormula::Saturating {
+                half_saturation: 300,
+            };
+            assert_eq!(saturating.produce(300, 20), 300);
+            assert_eq!(saturating.produce(900, 20), 450);
+            assert_eq!(saturating.produce(u128::MAX, 20), 600);
+            assert_eq!(
+                PayoffFormula::Saturating { half_saturation: 0 }.produce(0, 20),
+                0
+            );
+        }
+
+        /// The protocol fee is taken from each payout and sent to the router.
+        #[ink::test]
+        fn protocol_fee_is_sent_to_router() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: true,
+            });
+            let mut payouts = vec![(accounts.alice, 1_000), (accounts.bob, 55)];
+
+            // without a router, no fee is taken
+            game_public_good.game.configs.protocol_fee_bps = 250;
+            assert_eq!(game_public_good._collect_protocol_fee(&mut payouts), 0);
+
+            let router_balance = get_balance(accounts.django);
+            game_public_good.game.configs.router = Some(accounts.django);
+            assert_eq!(game_public_good._collect_protocol_fee(&mut payouts), 26);
+            assert_eq!(payouts, vec![(accounts.alice, 975), (accounts.bob, 54)]);
+            assert_eq!(get_balance(accounts.django), router_balance + 26);
+        }
+
+        /// The creator can reveal on behalf of a player.
+        #[ink::test]
+        fn creator_can_reveal_as_player() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: true,
+            });
+
+            // a non-creator cannot reveal for someone else
+            set_caller(accounts.bob);
+            assert!(game_public_good
+                .reveal_round_as_player(accounts.alice, (100, 144))
+                .is_err());
+
+            set_caller(accounts.alice);
+            assert!(game_public_good
+                .reveal_round_as_player(accounts.bob, (100, 144))
+                .is_ok());
+            // a player cannot reveal twice
+            assert_eq!(
+                game_public_good
+                    .reveal_round_as_player(accounts.bob, (100, 144))
+                    .err(),
+                Some(GameError::PlayerAlreadyRevealed)
+            );
+            assert_eq!(
+                game_public_good
+                    .game
+                    .current_round
+                    .as_ref()
+                    .unwrap()
+                    .player_reveals,
+                vec![(accounts.bob, (100, 144))]
+            );
+        }
+
+        /// The creator can close a round even if some players never revealed.
+        #[ink::test]
+        fn creator_can_force_complete_round() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: true,
+            });
+
+            // only alice reveals
+            set_caller(accounts.alice);
+            assert!(game_public_good.reveal_round((100, 144)).is_ok());
+            assert_eq!(
+                game_public_good.complete_round().err(),
+                Some(GameError::NotAllPlayersRevealed)
+            );
+
+            // only the creator can force the round to close
+            set_caller(accounts.bob);
+            assert!(game_public_good.force_complete_round().is_err());
+
+            set_caller(accounts.alice);
+            assert!(game_public_good.force_complete_round().is_ok());
+            // the next round has been prepared
+            assert_eq!(game_public_good.game.next_round_id, 3);
+
+            let found = ink::env::test::recorded_events().any(|e| {
+                matches!(
+                    <Event as scale::Decode>::decode(&mut &e.data[..]),
+                    Ok(Event::RoundForceClosed(_))
+                )
+            });
+            assert!(found);
+        }
+
+        /// Commits and reveals are rejected after their deadlines and anyone can close the round.
+        #[ink::test]
+        fn expired_round_can_be_closed_by_anyone() {
+            let accounts = get_accounts();
+            let mut game_public_good = PublicGood::new(GameConfigs {
+                max_players: 10,
+                min_players: 2,
+                min_round_contribution: Some(100),
+                max_round_contribution: Some(1_000),
+                round_reward_multiplier: Some(20),
+                post_round_actions: false,
+                round_timeout: Some(2),
+                max_rounds: Some(3),
+                join_fee: None,
+                join_deadline: None,
+                is_rounds_based: false,
+                non_reveal_policy: NonRevealPolicy::ForfeitToPot,
+                tie_policy: TiePolicy::Replay,
+                elimination: false,
+                move_count: None,
+                router: None,
+                creator: None,
+                ratings: None,
+                protocol_fee_bps: 0,
+                payoff_formula: PayoffFormula::Linear,
+                action_ratios: ActionRatios::default(),
+            })
+            .unwrap();
+
+            set_caller(accounts.alice);
+            assert!(game_public_good.join(accounts.alice).is_ok());
+            set_caller(accounts.bob);
+            assert!(game_public_good.join(accounts.bob).is_ok());
+            assert!(game_public_good.start_game().is_ok());
+
+            set_value(max_contribution(&game_public_good));
+
+            set_caller(accounts.alice);
+            assert!(game_public_good
+                .play_round(commitment_of(&game_public_good, accounts.alice))
+                .is_ok());
+
+            // the commit phase is over after `round_timeout` blocks
+            for _ in 0..3 {
+                ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            }
+            set_caller(accounts.bob);
+            assert_eq!(
+                game_public_good
+                    .play_round(commitment_of(&game_public_good, accounts.bob))
+                    .err(),
+                Some(GameError::CommitDeadlinePassed)
+            );
+
+            // the round can't be closed while the reveal phase is open
+            set_caller(accounts.charlie);
+            assert_eq!(
+                game_public_good.close_expired_round().err(),
+                Some(GameError::RoundNotExpired)
+            );
+
+            for _ in 0..2 {
+                ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            }
+            set_caller(accounts.alice);
+            assert_eq!(
+                game_public_good.reveal_round((100, 144)).err(),
+                Some(GameError::RevealDeadlinePassed)
+            );
+
+            // any account can close the expired round
+            set_caller(accounts.charlie);
+            assert!(game_public_good.close_expired_round().is_ok());
+            assert_eq!(game_public_good.game.next_round_id, 3);
+        }
+
+        /// A player who doesn't reveal is refunded their contribution minus the penalty.
+        #[ink::test]
+        fn non_revealer_is_refunded_minus_penalty() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: true,
+            });
+            game_public_good.game.configs.non_reveal_policy = NonRevealPolicy::RefundMinusPenalty {
+                penalty_percent: 10,
+            };
+
+            // charlie never reveals
+            set_caller(accounts.alice);
+            assert!(game_public_good.reveal_round((100, 144)).is_ok());
+            set_caller(accounts.bob);
+            assert!(game_public_good.reveal_round((100, 144)).is_ok());
+
+            set_caller(accounts.alice);
+            assert!(game_public_good.force_complete_round().is_ok());
+            // the penalty goes to the pot, and charlie still gets a share of the public good
+            assert_eq!(game_public_good.get_claimable(accounts.charlie), 900 + 200);
+            assert_eq!(game_public_good.get_claimable(accounts.alice), 900 + 200);
+
+            let penalty = ink::env::test::recorded_events().find_map(|e| {
+                match <Event as scale::Decode>::decode(&mut &e.data[..]) {
+                    Ok(Event::PlayerPenalized(data)) => Some((data.player, data.penalty)),
+                    _ => None,
+                }
+            });
+            assert_eq!(penalty, Some((accounts.charlie, 100)));
+        }
+
+        /// A player can't reveal once the round was closed without their reveal, their
+        /// contribution was already dealt with.
+        #[ink::test]
+        fn non_revealer_cannot_reveal_after_game_end() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: true,
+            });
+            game_public_good.game.configs.non_reveal_policy = NonRevealPolicy::RefundMinusPenalty {
+                penalty_percent: 10,
+            };
+
+            set_caller(accounts.alice);
+            assert!(game_public_good.reveal_round((100, 144)).is_ok());
+            assert!(game_public_good.force_end_game().is_ok());
+            let refund = game_public_good.get_claimable(accounts.bob);
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                game_public_good.reveal_round((100, 144)),
+                Err(GameError::GameNotStarted)
+            );
+            assert_eq!(game_public_good.get_claimable(accounts.bob), refund);
+        }
+
+        /// Join fees are refunded and endowments are returned to their funders.
+        #[ink::test]
+        fn join_fees_and_endowments_are_refunded() {
+            let accounts = get_accounts();
+            let mut game_public_good = PublicGood::default();
+            game_public_good.game.configs.join_fee = Some(10);
+
+            set_caller(accounts.alice);
+            set_value(500);
+            assert!(game_public_good.fund_contract().is_ok());
+
+            set_value(10);
+            assert!(game_public_good.join(accounts.alice).is_ok());
+            set_caller(accounts.bob);
+            assert!(game_public_good.join(accounts.bob).is_ok());
+
+            assert_eq!(
+                game_public_good._settle_game(),
+                vec![
+                    (accounts.alice, 10),
+                    (accounts.bob, 10),
+                    (accounts.alice, 500)
+                ]
+            );
+            // the refunds are claimed like any other balance
+            assert_eq!(game_public_good.get_claimable(accounts.alice), 510);
+            assert_eq!(game_public_good.get_claimable(accounts.bob), 10);
+            // nothing is refunded twice
+            assert_eq!(game_public_good._settle_game(), vec![]);
+            assert_eq!(game_public_good.get_claimable(accounts.alice), 510);
+        }
+
+        /// Ending the game credits the refunds, the game is terminated once they're claimed.
+        #[ink::test]
+        fn end_game_credits_the_refunds() {
+            let accounts = get_accounts();
+            let mut game_public_good = PublicGood::default();
+            game_public_good.game.configs.join_fee = Some(10);
+
+            set_value(10);
+            set_caller(accounts.alice);
+            assert!(game_public_good.join(accounts.alice).is_ok());
+            set_caller(accounts.bob);
+            assert!(game_public_good.join(accounts.bob).is_ok());
+            game_public_good.game.status = GameStatus::Ended;
+
+            assert_eq!(game_public_good.end_game(), Ok(()));
+            assert_eq!(game_public_good.get_claimable(accounts.alice), 10);
+            assert_eq!(game_public_good.get_claimable(accounts.bob), 10);
+            // nothing left to settle until the refunds are claimed
+            assert_eq!(
+                game_public_good.end_game(),
+                Err(GameError::BalancesNotSettled)
+            );
+        }
+
+        /// A player who doesn't reveal is only penalized on their round contribution, their
+        /// join fee is refunded in full.
+        #[ink::test]
+        fn non_revealer_is_penalized_once() {
+            let accounts = get_accounts();
+            let mut game_public_good = PublicGood::default();
+            game_public_good.game.configs.join_fee = Some(10);
+            game_public_good.game.configs.non_reveal_policy = NonRevealPolicy::RefundMinusPenalty {
+                penalty_percent: 10,
+            };
+
+            set_value(10);
+            set_caller(accounts.alice);
+            assert!(game_public_good.join(accounts.alice).is_ok());
+            set_caller(accounts.bob);
+            assert!(game_public_good.join(accounts.bob).is_ok());
+            assert!(game_public_good.start_game().is_ok());
+
+            set_value(max_contribution(&game_public_good));
+            for player in [accounts.alice, accounts.bob] {
+                set_caller(player);
+                assert!(game_public_good
+                    .play_round(commitment_of(&game_public_good, player))
+                    .is_ok());
+            }
+
+            // bob never reveals and loses 100 of their contribution
+            set_caller(accounts.alice);
+            assert!(game_public_good.reveal_round((100, 144)).is_ok());
+            assert!(game_public_good.force_complete_round().is_ok());
+            assert_eq!(
+                game_public_good.game.penalties.get(&accounts.bob),
+                Some(100)
+            );
+
+            assert_eq!(
+                game_public_good._settle_game(),
+                vec![(accounts.alice, 10), (accounts.bob, 10)]
+            );
+        }
+
+        /// A player who doesn't reveal can be excluded from the following rounds.
+        #[ink::test]
+        fn non_revealer_can_be_excluded() {
+            let accounts = get_accounts();
+            let mut game_public_good = setup_game(SetupTestGame {
+                join_game: true,
+                start_game: true,
+                play_commits: true,
+            });
+            game_public_good.game.configs.non_reveal_policy = NonRevealPolicy::Exclude;
+
+            set_caller(accounts.alice);
+            assert!(game_public_good.reveal_round((100, 144)).is_ok());
+            set_caller(accounts.bob);
+            assert!(game_public_good.reveal_round((100, 144)).is_ok());
+            set_caller(accounts.alice);
+            assert!(game_public_good.force_complete_round().is_ok());
+
+            assert_eq!(
+                game_public_good.game.players,
+                vec![accounts.alice, accounts.bob]
+            );
+            // charlie can't play the next round
+            set_caller(accounts.charlie);
+            assert_eq!(
+                game_public_good
+                    .play_round(commitment_of(&game_public_good, accounts.charlie))
+                    .err(),
+                Some(GameError::PlayerNotInGame)
+            );
         }
 
         #[ink::test]
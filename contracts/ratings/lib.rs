@@ -0,0 +1,427 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
+
+#[openbrush::contract]
+mod ratings {
+    use game_theory::logics::traits::ratings::*;
+    use game_theory::logics::traits::types::GameError;
+    use ink::env::call::{build_call, Call, ExecutionInput, Selector};
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use openbrush::contracts::access_control::{
+        extensions::enumerable::*, only_role, DEFAULT_ADMIN_ROLE,
+    };
+    use openbrush::modifiers;
+    use openbrush::traits::Storage;
+
+    /// Access control roles
+    /// Can report results without being a game of the trusted router, e.g. an oracle
+    pub const REPORTER: RoleType = ink::selector_id!("REPORTER");
+
+    /// The rating of a player who hasn't played yet
+    pub const INITIAL_RATING: u32 = 1200;
+    /// The maximum change of rating from a round between two players
+    pub const K_FACTOR: i64 = 32;
+    /// Only the moves below this are counted in the move histograms
+    pub const MAX_TRACKED_MOVES: u128 = 16;
+    /// The number of players kept in the leaderboard, the full ranking can be computed off-chain
+    /// from the `RatingUpdated` events
+    pub const LEADERBOARD_SIZE: usize = 100;
+
+    /// The expected score (per mille) of the higher rated player, by rating difference in steps
+    /// of 25 points, i.e. `1000 / (1 + 10^(-difference / 400))`. Differences above 800 points
+    /// use the last step.
+    const EXPECTED_SCORES: [i64; 33] = [
+        500, 536, 571, 606, 640, 673, 703, 733, 760, 785, 808, 830, 849, 867, 882, 896, 909, 920,
+        930, 939, 947, 954, 960, 965, 969, 973, 977, 980, 983, 985, 987, 989, 990,
+    ];
+
+    #[ink(event)]
+    pub struct RatingUpdated {
+        #[ink(topic)]
+        player: AccountId,
+        rating: u32,
+    }
+
+    /// The rating and stats of a player, across every reported game.
+    #[derive(scale::Encode, scale::Decode, PartialEq, Eq, Clone, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct PlayerStats {
+        /// The Elo rating of the player
+        pub rating: u32,
+        pub wins: u32,
+        pub losses: u32,
+        pub ties: u32,
+        /// The number of times the player revealed each move, by move
+        pub moves: Vec<u32>,
+    }
+
+    impl Default for PlayerStats {
+        fn default() -> Self {
+            Self {
+                rating: INITIAL_RATING,
+                wins: 0,
+                losses: 0,
+                ties: 0,
+                moves: Vec::new(),
+            }
+        }
+    }
+
+    /// Returns the score (per mille) of a player against an opponent, if they played against
+    /// each other: the winners of a round only played against its losers.
+    fn score(outcome: Outcome, other: Outcome) -> Option<i64> {
+        match (outcome, other) {
+            (Outcome::Win, Outcome::Loss) => Some(1000),
+            (Outcome::Loss, Outcome::Win) => Some(0),
+            (Outcome::Tie, Outcome::Tie) => Some(500),
+            _ => None,
+        }
+    }
+
+    /// Returns the expected score (per mille) of a player rated `rating` against an opponent
+    /// rated `other`, interpolated from `EXPECTED_SCORES`.
+    fn expected_score(rating: i64, other: i64) -> i64 {
+        let difference = (rating - other).abs().min(800);
+        let step = (difference / 25) as usize;
+        let next = EXPECTED_SCORES[(step + 1).min(EXPECTED_SCORES.len() - 1)];
+        let expected =
+            EXPECTED_SCORES[step] + (next - EXPECTED_SCORES[step]) * (difference % 25) / 25;
+
+        if rating >= other {
+            expected
+        } else {
+            1000 - expected
+        }
+    }
+
+    /// Keeps the Elo rating and stats of the players of the games which report to it.
+    ///
+    /// Only the games instantiated by the trusted router (see `GameRegistry`) can report,
+    /// the games aren't registered one by one.
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct PlayerRatings {
+        #[storage_field]
+        access: access_control::Data<enumerable::Members>,
+        /// The router whose games are allowed to report results
+        router: Option<AccountId>,
+        /// The rating and stats of each player
+        stats: Mapping<AccountId, PlayerStats>,
+        /// The `LEADERBOARD_SIZE` best rated players with their rating, from the highest rating
+        leaderboard: Vec<(AccountId, u32)>,
+    }
+
+    impl AccessControl for PlayerRatings {}
+
+    impl AccessControlEnumerable for PlayerRatings {}
+
+    impl Ratings for PlayerRatings {
+        /// Updates the ratings from the ratings before the round: each winner of the round
+        /// played against each of its losers, and each tied player against the others.
+        #[ink(message)]
+        fn report_round(&mut self, results: Vec<PlayerResult>) -> Result<(), GameError> {
+            self.ensure_reporter()?;
+
+            let ratings: Vec<i64> = results
+                .iter()
+                .map(|result| i64::from(self.get_rating(result.player)))
+                .collect();
+
+            for (index, result) in results.iter().enumerate() {
+                let mut change = 0;
+                for (other_index, other) in results.iter().enumerate() {
+                    if index == other_index {
+                        continue;
+                    }
+                    if let Some(score) = score(result.outcome, other.outcome) {
+                        change += K_FACTOR
+                            * (score - expected_score(ratings[index], ratings[other_index]))
+                            / 1000;
+                    }
+                }
+
+                let mut stats = self.stats.get(result.player).unwrap_or_default();
+                stats.rating = (ratings[index] + change).max(0) as u32;
+                match result.outcome {
+                    Outcome::Win => stats.wins += 1,
+                    Outcome::Loss => stats.losses += 1,
+                    Outcome::Tie => stats.ties += 1,
+                }
+                if result.choice < MAX_TRACKED_MOVES {
+                    let choice = result.choice as usize;
+                    if stats.moves.len() <= choice {
+                        stats.moves.resize(choice + 1, 0);
+                    }
+                    stats.moves[choice] += 1;
+                }
+
+                self.stats.insert(result.player, &stats);
+                self.update_leaderboard(result.player, stats.rating);
+                self.env().emit_event(RatingUpdated {
+                    player: result.player,
+                    rating: stats.rating,
+                });
+            }
+
+            Ok(())
+        }
+    }
+
+    impl PlayerRatings {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let mut instance = Self::default();
+            instance._init_with_admin(Self::env().caller());
+            instance
+        }
+
+        /// Get the rating of `account`, `INITIAL_RATING` if it never played.
+        #[ink(message)]
+        pub fn get_rating(&self, account: AccountId) -> u32 {
+            self.stats
+                .get(account)
+                .map_or(INITIAL_RATING, |stats| stats.rating)
+        }
+
+        /// Get the rating and stats of `account`, if it ever played.
+        #[ink(message)]
+        pub fn get_stats(&self, account: AccountId) -> Option<PlayerStats> {
+            self.stats.get(account)
+        }
+
+        /// Get the `n` players with the highest rating, from the highest rating.
+        ///
+        /// At most `LEADERBOARD_SIZE` players are returned. A listed player dropping to the last
+        /// place is removed, and the free place goes to the next player to be rated, so the
+        /// leaderboard is approximate once it has been full.
+        #[ink(message)]
+        pub fn top_players(&self, n: u32) -> Vec<(AccountId, u32)> {
+            self.leaderboard.iter().take(n as usize).copied().collect()
+        }
+
+        /// Allows every game instantiated by `router` to report results, or none if `None`.
+        #[ink(message)]
+        #[modifiers(only_role(DEFAULT_ADMIN_ROLE))]
+        pub fn set_router(&mut self, router: Option<AccountId>) -> Result<(), GameError> {
+            self.router = router;
+            Ok(())
+        }
+
+        /// Get the router whose games are allowed to report results.
+        #[ink(message)]
+        pub fn get_router(&self) -> Option<AccountId> {
+            self.router
+        }
+
+        /// Helper method to ensure that the caller is a reporter or a game of the router.
+        fn ensure_reporter(&self) -> Result<(), GameError> {
+            let caller = self.env().caller();
+            if self.has_role(REPORTER, caller) {
+                return Ok(());
+            }
+
+            let registered = self.router.map_or(false, |router| {
+                matches!(
+                    build_call::<Environment>()
+                        .call_type(Call::new().callee(router).gas_limit(0))
+                        .exec_input(
+                            ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                                "GameRegistry::is_registered_game"
+                            )))
+                            .push_arg(caller),
+                        )
+                        .returns::<bool>()
+                        .try_invoke(),
+                    Ok(Ok(true))
+                )
+            });
+            if !registered {
+                return Err(AccessControlError::MissingRole.into());
+            }
+            Ok(())
+        }
+
+        /// Moves `player` to its place in the leaderboard, if it's one of the best rated.
+        ///
+        /// A player dropping to the last place of a full leaderboard is removed instead, since
+        /// unlisted players may now rate higher.
+        fn update_leaderboard(&mut self, player: AccountId, rating: u32) {
+            let was_full = self.leaderboard.len() >= LEADERBOARD_SIZE;
+            self.leaderboard.retain(|(rated, _)| *rated != player);
+            let position = self
+                .leaderboard
+                .partition_point(|(_, other_rating)| *other_rating >= rating);
+            let dropped_to_last = was_full && position == self.leaderboard.len();
+            if position < LEADERBOARD_SIZE && !dropped_to_last {
+                self.leaderboard.insert(position, (player, rating));
+                self.leaderboard.truncate(LEADERBOARD_SIZE);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn report(ratings: &mut PlayerRatings, results: &[(AccountId, u128, Outcome)]) {
+            let results = results
+                .iter()
+                .map(|(player, choice, outcome)| PlayerResult {
+                    player: *player,
+                    choice: *choice,
+                    outcome: *outcome,
+                })
+                .collect();
+            assert_eq!(ratings.report_round(results), Ok(()));
+        }
+
+        fn new_ratings() -> PlayerRatings {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut ratings = PlayerRatings::new();
+            assert!(ratings.grant_role(REPORTER, accounts.alice).is_ok());
+            ratings
+        }
+
+        #[ink::test]
+        fn expected_score_is_symmetric() {
+            assert_eq!(expected_score(1200, 1200), 500);
+            assert_eq!(expected_score(1400, 1200), 760);
+            assert_eq!(expected_score(1200, 1400), 240);
+            assert_eq!(expected_score(1210, 1200), 514);
+            assert_eq!(expected_score(3000, 1000), 990);
+        }
+
+        #[ink::test]
+        fn winner_takes_rating_from_loser() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut ratings = new_ratings();
+            assert_eq!(ratings.get_rating(accounts.bob), INITIAL_RATING);
+            assert_eq!(ratings.get_stats(accounts.bob), None);
+
+            report(
+                &mut ratings,
+                &[
+                    (accounts.bob, 1, Outcome::Win),
+                    (accounts.charlie, 0, Outcome::Loss),
+                ],
+            );
+            assert_eq!(ratings.get_rating(accounts.bob), 1216);
+            assert_eq!(ratings.get_rating(accounts.charlie), 1184);
+            assert_eq!(
+                ratings.get_stats(accounts.charlie),
+                Some(PlayerStats {
+                    rating: 1184,
+                    wins: 0,
+                    losses: 1,
+                    ties: 0,
+                    moves: vec![1],
+                })
+            );
+
+            // a tie between equal players doesn't change their ratings
+            report(
+                &mut ratings,
+                &[
+                    (accounts.django, 2, Outcome::Tie),
+                    (accounts.eve, 2, Outcome::Tie),
+                ],
+            );
+            assert_eq!(ratings.get_rating(accounts.django), INITIAL_RATING);
+            assert_eq!(
+                ratings.get_stats(accounts.eve).unwrap().moves,
+                vec![0, 0, 1]
+            );
+        }
+
+        #[ink::test]
+        fn top_players_are_sorted_by_rating() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut ratings = new_ratings();
+
+            report(
+                &mut ratings,
+                &[
+                    (accounts.bob, 0, Outcome::Loss),
+                    (accounts.charlie, 1, Outcome::Win),
+                    (accounts.django, 1, Outcome::Win),
+                ],
+            );
+            assert_eq!(
+                ratings.top_players(2),
+                vec![(accounts.charlie, 1216), (accounts.django, 1216)]
+            );
+            assert_eq!(
+                ratings.top_players(10),
+                vec![
+                    (accounts.charlie, 1216),
+                    (accounts.django, 1216),
+                    (accounts.bob, 1168),
+                ]
+            );
+        }
+
+        #[ink::test]
+        fn leaderboard_keeps_the_best_rated_players() {
+            let mut ratings = new_ratings();
+            let player = |index: usize| AccountId::from([index as u8 + 1; 32]);
+            for index in 0..=LEADERBOARD_SIZE {
+                ratings.update_leaderboard(player(index), 1000 + index as u32);
+            }
+            // the worst rated player doesn't fit in the leaderboard
+            ratings.update_leaderboard(AccountId::from([0xff; 32]), 999);
+
+            let top = ratings.top_players(u32::MAX);
+            assert_eq!(top.len(), LEADERBOARD_SIZE);
+            assert_eq!(
+                top[0],
+                (player(LEADERBOARD_SIZE), 1000 + LEADERBOARD_SIZE as u32)
+            );
+            assert_eq!(top[LEADERBOARD_SIZE - 1], (player(1), 1001));
+        }
+
+        #[ink::test]
+        fn player_dropping_to_the_last_place_leaves_a_full_leaderboard() {
+            let mut ratings = new_ratings();
+            let player = |index: usize| AccountId::from([index as u8 + 1; 32]);
+            for index in 0..LEADERBOARD_SIZE {
+                ratings.update_leaderboard(player(index), 1000 + index as u32);
+            }
+
+            // unlisted players may rate higher than 900
+            ratings.update_leaderboard(player(LEADERBOARD_SIZE - 1), 900);
+            let top = ratings.top_players(u32::MAX);
+            assert_eq!(top.len(), LEADERBOARD_SIZE - 1);
+            assert!(!top
+                .iter()
+                .any(|(rated, _)| *rated == player(LEADERBOARD_SIZE - 1)));
+
+            // a listed player dropping in the middle keeps their place
+            ratings.update_leaderboard(player(50), 1010);
+            assert_eq!(ratings.top_players(u32::MAX).len(), LEADERBOARD_SIZE - 1);
+            assert!(ratings.top_players(u32::MAX).contains(&(player(50), 1010)));
+        }
+
+        #[ink::test]
+        fn only_admin_can_trust_a_router() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut ratings = new_ratings();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(ratings.set_router(Some(accounts.django)).is_err());
+            // without a trusted router, only the reporters can report
+            assert_eq!(
+                ratings.report_round(Vec::new()),
+                Err(AccessControlError::MissingRole.into())
+            );
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(ratings.set_router(Some(accounts.django)), Ok(()));
+            assert_eq!(ratings.get_router(), Some(accounts.django));
+        }
+    }
+}
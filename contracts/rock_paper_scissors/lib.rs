@@ -1,17 +1,104 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
 
 pub use self::rock_paper_scissors::{RockPaperScissors, RockPaperScissorsRef};
 
+use game_theory::logics::traits::types::{
+    ActionRatios, ConfigError, GameConfigs, GameError, NonRevealPolicy, PayoffFormula, TiePolicy,
+};
+
+/// The configurations used by the `default` constructor: a single game between 2 players.
+///
+/// Up to `max_players` can play, see `RockPaperScissors::winners` for how a round between more
+/// than 2 players is decided.
+pub fn default_configs() -> GameConfigs {
+    GameConfigs {
+        max_players: 2,
+        min_players: 2,
+        min_round_contribution: Some(1),
+        max_round_contribution: Some(10000),
+        round_reward_multiplier: None,
+        post_round_actions: false,
+        round_timeout: None,
+        max_rounds: None,
+        join_fee: None,
+        join_deadline: None,
+        is_rounds_based: false,
+        non_reveal_policy: NonRevealPolicy::ForfeitToPot,
+        tie_policy: TiePolicy::Replay,
+        elimination: false,
+        move_count: None,
+        router: None,
+        creator: None,
+        ratings: None,
+        protocol_fee_bps: 0,
+        payoff_formula: PayoffFormula::Linear,
+        action_ratios: ActionRatios::default(),
+    }
+}
+
+/// The default number of moves a player can reveal: 0 (rock), 1 (paper) and 2 (scissors).
+///
+/// Any odd number of moves can be configured with `GameConfigs::move_count`, e.g. 5 for
+/// rock paper scissors lizard spock.
+pub const DEFAULT_MOVES: u8 = 3;
+
+/// Returns true if `choice` beats `other` in a game of `moves` moves.
+///
+/// The dominance is a balanced tournament: each move beats the `(moves - 1) / 2` moves right
+/// before it (modulo `moves`) and is beaten by the ones right after it. With 3 moves, paper
+/// beats rock, scissors beat paper and rock beats scissors. Both moves must be lower than `moves`.
+pub fn beats(choice: u128, other: u128, moves: u128) -> bool {
+    let distance = (moves + choice - other) % moves;
+    (1..=(moves - 1) / 2).contains(&distance)
+}
+
+/// Validates the configurations of a rock paper scissors game.
+///
+/// Rounds are settled between at least two players. A series (`is_rounds_based`) needs
+/// `max_rounds`, the length of the series, and can't be combined with `elimination`.
+pub fn validate_configs(configs: &GameConfigs) -> Result<(), GameError> {
+    configs.validate()?;
+
+    if configs.min_players < 2 {
+        return Err(ConfigError::UnsupportedPlayerCount.into());
+    }
+    if configs.is_rounds_based && configs.max_rounds.is_none() {
+        return Err(ConfigError::UnsupportedRoundCount.into());
+    }
+    if configs.is_rounds_based && configs.elimination {
+        return Err(ConfigError::IncompatibleModes.into());
+    }
+    // the dominance is only balanced with an odd number of moves
+    if let Some(moves) = configs.move_count {
+        if moves < DEFAULT_MOVES || moves % 2 == 0 {
+            return Err(ConfigError::UnsupportedMoveCount.into());
+        }
+    }
+
+    Ok(())
+}
+
 // noinspection ALL
 #[openbrush::contract]
 pub mod rock_paper_scissors {
+    use game_theory::ensure;
+    use game_theory::logics::impls::{
+        basic::data,
+        escrow::EscrowInternal,
+        lifecycle::{Hooks, LifecycleInternal},
+    };
+    use game_theory::logics::traits::admin::*;
     use game_theory::logics::traits::basic::*;
+    use game_theory::logics::traits::escrow::*;
     use game_theory::logics::traits::lifecycle::*;
+    use game_theory::logics::traits::ratings::{Outcome, PlayerResult};
     use game_theory::logics::traits::types::{
-        GameConfigs, GameError, GameRound, GameStatus, RoundStatus,
+        GameConfigs, GameError, GameRound, GameStatus, RoundPayouts, RoundStatus, TiePolicy,
     };
+    use game_theory::logics::traits::upgradeable::*;
     use ink::codegen::EmitEvent;
-    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::env::call::{build_call, Call, ExecutionInput, Selector};
     use ink::prelude::vec::Vec;
     use openbrush::contracts::access_control::extensions::enumerable::*;
     use openbrush::contracts::access_control::only_role;
@@ -87,6 +174,61 @@ pub mod rock_paper_scissors {
         winners: Vec<(AccountId, u128)>,
         round_id: u8,
         total_contribution: u128,
+        /// The protocol fee taken from the winnings
+        protocol_fee: u128,
+    }
+
+    #[ink(event)]
+    pub struct PlayerLeft {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        player: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct GameCancelled {
+        #[ink(topic)]
+        game_address: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RouterNotificationFailed {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        router: AccountId,
+        // the selector of the `RouterCallbacks` message which failed
+        callback: [u8; 4],
+    }
+
+    #[ink(event)]
+    pub struct GameSettled {
+        #[ink(topic)]
+        game_address: AccountId,
+        // the refunds credited when the game is settled (join fees and unused endowments), or
+        // the remaining balance sent to the creator when the contract is terminated
+        transfers: Vec<(AccountId, Balance)>,
+    }
+
+    #[ink(event)]
+    pub struct BalanceClaimed {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        player: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct PlayerPenalized {
+        #[ink(topic)]
+        game_address: AccountId,
+        #[ink(topic)]
+        player: AccountId,
+        round_id: u8,
+        // the part of the round contribution lost for not revealing
+        penalty: u128,
     }
 
     /// A single game storage.
@@ -96,61 +238,285 @@ pub mod rock_paper_scissors {
     pub struct RockPaperScissors {
         #[storage_field]
         access: access_control::Data<enumerable::Members>,
-        /// Stores the list of players for this game instance
-        players: Vec<AccountId>,
-        /// The status of the current game
-        status: GameStatus,
-        /// A list of all the rounds that have been played
-        rounds: Vec<GameRound>,
-        /// The current round of the game
-        current_round: Option<GameRound>,
-        /// The id of the next round
-        next_round_id: u8,
-        /// Account that created the instance of this game
-        creator: AccountId,
-        /// The configurations of the game
-        configs: GameConfigs,
+        /// The shared game state (players, rounds, configs)
+        #[storage_field]
+        game: data::Data,
+        /// The pot carried over to the next rounds: the pot of the tied rounds
+        /// (see `TiePolicy::Replay`) and, in a series, the pot of every round until it's won
+        carried_pot: u128,
+        /// The contributions of the players who revealed in the rounds whose pot is carried
+        /// over, to refund the carried pot if the game ends without a winner
+        carried_stakes: Vec<(AccountId, u128)>,
+        /// The number of rounds won by each player, in a series
+        scores: Vec<(AccountId, u32)>,
     }
 
     impl RockPaperScissors {
         /// Constructor that initializes the RockPaperScissors struct
-        #[ink(constructor)]
-        pub fn new(configs: GameConfigs) -> Self {
+        #[ink(constructor, payable)]
+        pub fn new(configs: GameConfigs) -> Result<Self, GameError> {
             let mut instance = Self {
                 access: Default::default(),
-                creator: <Self as DefaultEnv>::env().caller(),
-                players: Vec::new(),
-                status: GameStatus::Ready,
-                rounds: Vec::new(),
-                current_round: None,
-                next_round_id: 1,
-                configs,
+                game: Default::default(),
+                carried_pot: 0,
+                carried_stakes: Vec::new(),
+                scores: Vec::new(),
             };
             let caller = <Self as DefaultEnv>::env().caller();
 
+            // fails if the configs are not valid for this game, see `validate_configs`
+            instance._init_game(configs)?;
             instance._init_with_admin(caller);
             instance
                 .grant_role(CREATOR, caller)
                 .expect("Should grant CREATOR role");
+            // the account creating the game through the router shares the role with the router
+            let creator = instance.game.created_by;
+            if creator != caller {
+                instance
+                    .grant_role(CREATOR, creator)
+                    .expect("Should grant CREATOR role");
+            }
 
-            instance
+            Ok(instance)
         }
 
         /// A default constructor that initializes this game with 2 players
         #[ink(constructor)]
         pub fn default() -> Self {
-            Self::new(GameConfigs {
-                max_players: 2,
-                min_players: 2,
-                min_round_contribution: Some(1),
-                max_round_contribution: Some(10000),
-                round_reward_multiplier: None,
-                post_round_actions: false,
-                round_timeout: None,
-                max_rounds: None,
-                join_fee: None,
-                is_rounds_based: false,
-            })
+            Self::new(crate::default_configs()).expect("Default configs should be valid")
+        }
+
+        /// Get the pot carried over from the tied rounds.
+        #[ink(message)]
+        pub fn get_carried_pot(&self) -> u128 {
+            self.carried_pot
+        }
+
+        /// Get the number of rounds won by each player.
+        ///
+        /// Only rounds of a series (see `GameConfigs::is_rounds_based`) are scored: a series
+        /// of `max_rounds` is won by the first player to win more than half of them.
+        #[ink(message)]
+        pub fn get_score(&self) -> Vec<(AccountId, u32)> {
+            self.game
+                .players
+                .iter()
+                .map(|player| (*player, self.wins_of(player)))
+                .collect()
+        }
+
+        fn wins_of(&self, player: &AccountId) -> u32 {
+            self.scores
+                .iter()
+                .find(|(scorer, _)| scorer == player)
+                .map_or(0, |(_, wins)| *wins)
+        }
+
+        /// Returns the winner of the series, if a player has won enough rounds.
+        fn series_winner(&self) -> Option<AccountId> {
+            let max_rounds = self.game.configs.max_rounds?;
+            let wins_needed = max_rounds / 2 + 1;
+
+            self.scores
+                .iter()
+                .find(|(_, wins)| *wins >= wins_needed)
+                .map(|(player, _)| *player)
+        }
+
+        /// The number of moves a player can reveal.
+        fn moves(&self) -> u128 {
+            u128::from(self.game.configs.move_count.unwrap_or(crate::DEFAULT_MOVES))
+        }
+
+        /// Returns the winners of a round between the players who revealed, or `None` on a tie.
+        ///
+        /// A player who is the only one to reveal wins. Otherwise the round is decided when one
+        /// of the moves revealed beats every other move revealed: every player who revealed it
+        /// wins. A single move, or moves which don't have such a winner (e.g. all three moves
+        /// of rock paper scissors), is a tie. The result doesn't depend on the order of the reveals.
+        fn winners(&self, round: &GameRound) -> Option<Vec<AccountId>> {
+            if let [(player, _)] = round.player_reveals.as_slice() {
+                return Some(Vec::from([*player]));
+            }
+
+            let mut symbols: Vec<u128> = Vec::new();
+            for (_, (choice, _)) in round.player_reveals.iter() {
+                if !symbols.contains(choice) {
+                    symbols.push(*choice);
+                }
+            }
+            if symbols.len() < 2 {
+                return None;
+            }
+
+            let moves = self.moves();
+            let winning = symbols.iter().copied().find(|symbol| {
+                symbols
+                    .iter()
+                    .all(|other| other == symbol || crate::beats(*symbol, *other, moves))
+            })?;
+
+            Some(
+                round
+                    .player_reveals
+                    .iter()
+                    .filter(|(_, (choice, _))| *choice == winning)
+                    .map(|(player, _)| *player)
+                    .collect(),
+            )
+        }
+
+        /// Reports the outcome of a round to the ratings contract (if any), see `Ratings`.
+        ///
+        /// Only the rounds played by at least two players are reported. The result of the call
+        /// is ignored on purpose, a faulty ratings contract must never block the game.
+        fn report_round(&self, round: &GameRound, winners: Option<&[AccountId]>) {
+            let ratings = match self.game.configs.ratings {
+                Some(ratings) if round.player_reveals.len() > 1 => ratings,
+                _ => return,
+            };
+
+            let results: Vec<PlayerResult> = round
+                .player_reveals
+                .iter()
+                .map(|(player, (choice, _))| PlayerResult {
+                    player: *player,
+                    choice: *choice,
+                    outcome: match winners {
+                        Some(winners) if winners.contains(player) => Outcome::Win,
+                        Some(_) => Outcome::Loss,
+                        None => Outcome::Tie,
+                    },
+                })
+                .collect();
+
+            let _ = build_call::<Environment>()
+                .call_type(Call::new().callee(ratings).gas_limit(0))
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "Ratings::report_round"
+                    )))
+                    .push_arg(results),
+                )
+                .returns::<Result<(), GameError>>()
+                .try_invoke();
+        }
+
+        /// Returns the contribution of `player` to `round`.
+        fn contribution_of(round: &GameRound, player: &AccountId) -> u128 {
+            round
+                .player_contributions
+                .iter()
+                .find(|(contributor, _)| contributor == player)
+                .map_or(0, |(_, contribution)| *contribution)
+        }
+
+        /// Splits `pot` between `players` pro rata to their contribution to `round` (or equally
+        /// if none of them contributed). The remainder goes to the lowest account id.
+        fn split_pot(
+            round: &GameRound,
+            players: &[AccountId],
+            pot: u128,
+        ) -> Vec<(AccountId, u128)> {
+            let stakes: Vec<(AccountId, u128)> = players
+                .iter()
+                .map(|player| (*player, Self::contribution_of(round, player)))
+                .collect();
+            Self::split_pro_rata(&stakes, pot)
+        }
+
+        /// Splits `pot` between the players pro rata to their `stakes` (or equally if none of
+        /// them staked anything). The remainder goes to the lowest account id, so the split
+        /// doesn't depend on the order the players revealed in.
+        fn split_pro_rata(stakes: &[(AccountId, u128)], pot: u128) -> Vec<(AccountId, u128)> {
+            let mut stakes = stakes.to_vec();
+            stakes.sort_by_key(|(player, _)| *player);
+            let total_stake: u128 = stakes.iter().map(|(_, stake)| stake).sum();
+
+            let mut payouts: Vec<(AccountId, u128)> = stakes
+                .iter()
+                .map(|(player, stake)| {
+                    let share = if total_stake == 0 {
+                        pot / stakes.len() as u128
+                    } else {
+                        pot.saturating_mul(*stake) / total_stake
+                    };
+                    (*player, share)
+                })
+                .collect();
+
+            let paid: u128 = payouts.iter().map(|(_, share)| share).sum();
+            if let Some((_, share)) = payouts.first_mut() {
+                *share += pot - paid;
+            }
+
+            payouts
+        }
+
+        /// Carries `pot` over to the next rounds, along with the stakes of the players who
+        /// revealed in `round`.
+        fn carry_pot(&mut self, round: &GameRound, pot: u128) {
+            self.carried_pot = pot;
+            for (player, _) in round.player_reveals.iter() {
+                let stake = Self::contribution_of(round, player);
+                match self.carried_stakes.iter_mut().find(|(p, _)| p == player) {
+                    Some((_, carried)) => *carried += stake,
+                    None => self.carried_stakes.push((*player, stake)),
+                }
+            }
+        }
+
+        /// Empties the carried pot, returns it split between the players who put it in,
+        /// pro rata to their stakes.
+        fn refund_carried_pot(&mut self) -> Vec<(AccountId, u128)> {
+            let pot = core::mem::take(&mut self.carried_pot);
+            let stakes = core::mem::take(&mut self.carried_stakes);
+            if pot == 0 || stakes.is_empty() {
+                return Vec::new();
+            }
+            Self::split_pro_rata(&stakes, pot)
+        }
+
+        /// Credits the pot carried over once the game has ended: to the winner of the series,
+        /// minus the protocol fee, or back to the players who put it in if there's no winner.
+        fn release_carried_pot(&mut self) {
+            if self.carried_pot == 0 {
+                return;
+            }
+
+            let payouts = match self.series_winner() {
+                Some(winner) => {
+                    let mut payouts = Vec::from([(winner, core::mem::take(&mut self.carried_pot))]);
+                    self.carried_stakes = Vec::new();
+                    let fee = self._collect_protocol_fee(&mut payouts);
+                    if fee > 0 {
+                        // the fee is reported along with the last round of the series
+                        let round_id = self.game.current_round.as_ref().map_or(0, |round| round.id);
+                        self._notify_round_completed(round_id, fee);
+                    }
+                    payouts
+                }
+                None => self.refund_carried_pot(),
+            };
+            for (player, amount) in payouts {
+                self._credit(player, amount);
+            }
+        }
+
+        /// Returns true if a tied round is settled by refunding the players rather than replayed.
+        fn refunds_tie(&self, round: &GameRound) -> bool {
+            // the ties of a series are always replayed
+            if self.game.configs.is_rounds_based {
+                return false;
+            }
+
+            self.game.configs.tie_policy == TiePolicy::Refund
+                || self
+                    .game
+                    .configs
+                    .max_rounds
+                    .map_or(false, |max_rounds| u32::from(round.id) >= max_rounds)
         }
 
         /// Internal methods
@@ -158,8 +524,8 @@ pub mod rock_paper_scissors {
         pub fn emit_game_created(&mut self) -> Result<(), GameError> {
             self.env().emit_event(GameCreated {
                 game_id: self.env().account_id(),
-                creator: self.creator,
-                configs: self.configs.clone(),
+                creator: self.game.created_by,
+                configs: self.game.configs.clone(),
             });
 
             Ok(())
@@ -169,270 +535,329 @@ pub mod rock_paper_scissors {
         pub fn emit_game_started(&mut self) -> Result<(), GameError> {
             self.env().emit_event(GameStarted {
                 game_address: self.env().account_id(),
-                players: self.players.clone(),
+                players: self.game.players.clone(),
             });
 
             Ok(())
         }
+    }
 
-        // TODO: this is would be on the front end
+    impl Basic for RockPaperScissors {}
+    impl Lifecycle for RockPaperScissors {
+        /// Credits the pot of a series to its winner, then terminates the game.
+        ///
+        /// The game is only terminated once every balance has been claimed, so the winner has
+        /// to claim their prize (and the players their deposits) before `end_game` is called
+        /// again.
         #[ink(message)]
-        pub fn hash_commitment(&self, input: u128, nonce: u128) -> Result<Hash, GameError> {
-            let data = [input.to_le_bytes(), nonce.to_le_bytes()].concat();
-            let mut output = <Blake2x256 as HashOutput>::Type::default();
-            ink::env::hash_bytes::<Blake2x256>(&data, &mut output);
-            Ok(output.into())
+        fn end_game(&mut self) -> Result<(), GameError> {
+            // ensure the game is in ended (or cancelled) state
+            ensure!(
+                self.game.status == GameStatus::Ended || self.game.status == GameStatus::Cancelled,
+                GameError::InvalidGameState
+            );
+
+            // the pot is credited along with the refunds of the deposits
+            self.release_carried_pot();
+            self._settle_game();
+            if !self._is_settled() {
+                return Ok(());
+            }
+
+            // terminate the contract
+            self._terminate_game()
         }
     }
+    impl Escrow for RockPaperScissors {}
 
-    impl Basic for RockPaperScissors {
+    /// Only the creator of the game can migrate it to new code.
+    impl Upgradeable for RockPaperScissors {
         #[ink(message)]
-        fn get_configs(&self) -> GameConfigs {
-            self.configs.clone()
+        #[modifiers(only_role(CREATOR))]
+        fn set_code_hash(&mut self, code_hash: Hash) -> Result<(), GameError> {
+            Self::env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| GameError::FailedToSetCodeHash)
+        }
+    }
+
+    impl Admin for RockPaperScissors {
+        #[ink(message, payable)]
+        #[modifiers(only_role(CREATOR))]
+        fn add_player_to_game(&mut self, player: AccountId) -> Result<u8, GameError> {
+            // add player to state
+            let players_count = self._add_player(player)?;
+            // any paid amount is held as the player's deposit, to be refunded when the game ends
+            let value = Self::env().transferred_value();
+            if value > 0 {
+                self.game.join_fees.push((player, value));
+            }
+            Ok(players_count)
         }
 
         #[ink(message)]
-        fn get_players(&self) -> Vec<AccountId> {
-            self.players.clone()
+        #[modifiers(only_role(CREATOR))]
+        fn play_round_as_player(
+            &mut self,
+            as_player: AccountId,
+            commitment: Hash,
+        ) -> Result<(), GameError> {
+            self._commit(as_player, commitment, Self::env().transferred_value())
         }
 
         #[ink(message)]
-        fn get_status(&self) -> GameStatus {
-            self.status
+        #[modifiers(only_role(CREATOR))]
+        fn reveal_round_as_player(
+            &mut self,
+            as_player: AccountId,
+            reveal: (u128, u128),
+        ) -> Result<(), GameError> {
+            self._reveal(as_player, reveal)
         }
 
         #[ink(message)]
-        fn get_current_round(&self) -> Option<GameRound> {
-            self.current_round.clone()
+        #[modifiers(only_role(CREATOR))]
+        fn force_complete_round(&mut self) -> Result<(), GameError> {
+            self._force_close_round()
         }
 
-        #[ink(message, payable)]
-        fn join(&mut self, player: AccountId) -> Result<u8, GameError> {
-            if Self::env().caller() != player {
-                return Err(GameError::CallerMustMatchNewPlayer);
+        #[ink(message)]
+        #[modifiers(only_role(CREATOR))]
+        fn force_end_game(&mut self) -> Result<(), GameError> {
+            // close the current round if it's still open
+            if self.game.status == GameStatus::OnGoing
+                && self.game.current_round.as_ref().unwrap().status != RoundStatus::Ended
+            {
+                self._force_close_round()?;
             }
 
-            if self.players.len() >= self.configs.max_players.into() {
-                return Err(GameError::MaxPlayersReached);
+            if self.game.status != GameStatus::Ended {
+                self.game.status = GameStatus::Ended;
+                self._emit_game_ended_event();
+                self._notify_game_ended();
             }
-
-            if self.players.contains(&player) {
-                return Err(GameError::PlayerAlreadyJoined);
-            };
-
-            if let Some(fees) = self.configs.join_fee {
-                if Self::env().transferred_value() < fees {
-                    return Err(GameError::InsufficientJoiningFees);
-                }
+            // the pot of an unfinished series goes back to the players
+            self.release_carried_pot();
+
+            // refund the deposits, the contract is only terminated once every player has
+            // claimed their balance, `end_game` can be used otherwise
+            self._settle_game();
+            if self._is_settled() {
+                self._terminate_game()?;
             }
 
-            self.players.push(player);
-
-            Self::env().emit_event(PlayerJoined {
-                game_address: Self::env().account_id(),
-                player: Self::env().caller(),
-            });
-
-            Ok(self.players.len() as u8)
+            Ok(())
         }
-    }
 
-    impl Lifecycle for RockPaperScissors {
         #[ink(message, payable)]
-        fn start_game(&mut self) -> Result<(), GameError> {
-            if self.players.len() < self.configs.min_players.into() {
-                return Err(GameError::NotEnoughPlayers);
-            }
-
-            if self.status != GameStatus::Ready {
-                return Err(GameError::InvalidGameState);
+        fn fund_contract(&mut self) -> Result<(), GameError> {
+            // keep track of the endowment, what's left of it is returned when the game ends
+            let value = Self::env().transferred_value();
+            if value > 0 {
+                self.game.endowments.push((Self::env().caller(), value));
             }
+            Ok(())
+        }
+    }
 
-            self.current_round = Some(GameRound {
-                id: self.next_round_id,
-                status: RoundStatus::Ready,
-                player_commits: Vec::new(),
-                player_reveals: Vec::new(),
-                player_contributions: Vec::new(),
-                total_contribution: 0,
-                total_reward: 0,
-            });
-
-            self.status = GameStatus::OnGoing;
-            self.next_round_id += 1;
+    impl Hooks for RockPaperScissors {
+        fn _validate_configs(configs: &GameConfigs) -> Result<(), GameError> {
+            crate::validate_configs(configs)
+        }
 
-            Self::env().emit_event(GameStarted {
-                game_address: Self::env().account_id(),
-                players: self.players.clone(),
-            });
+        fn _on_reveal(
+            &mut self,
+            _player: AccountId,
+            reveal: (u128, u128),
+        ) -> Result<(), GameError> {
+            if reveal.0 >= self.moves() {
+                return Err(GameError::InvalidChoice);
+            }
 
             Ok(())
         }
 
-        #[ink(message, payable)]
-        fn play_round(&mut self, commitment: Hash) -> Result<(), GameError> {
-            if self.status != GameStatus::OnGoing {
-                return Err(GameError::InvalidGameState);
+        /// Players who didn't reveal before the round was closed forfeit their contribution to
+        /// the players who did, if nobody revealed the pot is kept as per the non-reveal policy
+        /// and the pot carried over from the previous rounds is refunded.
+        ///
+        /// The winners split the pot pro rata to their contribution. A tie is replayed with the
+        /// pot carried over, or refunded, see `TiePolicy`. In a series, the pot of each round is
+        /// escrowed until `end_game` and the winners of the round score a point. With
+        /// `elimination`, the pot is carried over until a single player remains.
+        fn _settle_round(&mut self, round: &GameRound) -> Result<RoundPayouts, GameError> {
+            // the round has been abandoned and the game is over, the pot carried over from the
+            // previous rounds goes back to the players who put it in
+            if round.player_reveals.is_empty() {
+                return Ok(RoundPayouts::refunds(self.refund_carried_pot()));
             }
 
-            if self.current_round.is_none() {
-                return Err(GameError::NoCurrentRound);
+            let winners = self.winners(round);
+            self.report_round(round, winners.as_deref());
+
+            let pot = round.total_contribution + self.carried_pot;
+            let winners = match winners {
+                Some(winners) => winners,
+                None if self.refunds_tie(round) => {
+                    // each player gets their contribution back, along with a share of the
+                    // carried pot
+                    let players: Vec<AccountId> = round
+                        .player_reveals
+                        .iter()
+                        .map(|(player, _)| *player)
+                        .collect();
+                    let mut payouts = Self::split_pot(round, &players, self.carried_pot);
+                    for (player, amount) in payouts.iter_mut() {
+                        *amount += Self::contribution_of(round, player);
+                    }
+                    self.carried_pot = 0;
+                    self.carried_stakes = Vec::new();
+                    return Ok(RoundPayouts::refunds(payouts));
+                }
+                None => {
+                    self.carry_pot(round, pot);
+                    return Ok(RoundPayouts::default());
+                }
+            };
+
+            if self.game.configs.is_rounds_based {
+                self.carry_pot(round, pot);
+                for winner in winners {
+                    let wins = self.wins_of(&winner) + 1;
+                    self.scores.retain(|(scorer, _)| *scorer != winner);
+                    self.scores.push((winner, wins));
+                }
+                return Ok(RoundPayouts::default());
             }
 
-            let current_round = self.current_round.as_mut().unwrap();
-            if current_round.status == RoundStatus::Ready {
-                current_round.status = RoundStatus::OnGoing
+            if self.game.configs.elimination {
+                self.game.players.retain(|player| winners.contains(player));
+                if self.game.players.len() > 1 {
+                    self.carry_pot(round, pot);
+                    return Ok(RoundPayouts::default());
+                }
             }
 
-            let caller = Self::env().caller();
+            self.carried_pot = 0;
+            self.carried_stakes = Vec::new();
+            Ok(RoundPayouts::winnings(Self::split_pot(
+                round, &winners, pot,
+            )))
+        }
 
-            if let Some(_p) = current_round
-                .player_commits
-                .iter()
-                .find(|(c, _)| c == &caller)
-            {
-                return Err(GameError::PlayerAlreadyCommitted);
+        /// The game is over once a round (or the series) has been decided, or a round has been
+        /// abandoned by every player. With `elimination`, it's over once a single player remains.
+        fn _is_game_over(&self, last_round: &GameRound) -> bool {
+            if last_round.player_reveals.is_empty() {
+                return true;
+            }
+            if self.game.configs.is_rounds_based {
+                return self.series_winner().is_some();
             }
 
-            if let Some(min_round_contribution) = self.configs.min_round_contribution {
-                if Self::env().transferred_value() < min_round_contribution {
-                    return Err(GameError::InvalidRoundContribution);
-                }
+            match self.winners(last_round) {
+                Some(_) => !self.game.configs.elimination || self.game.players.len() <= 1,
+                None => self.refunds_tie(last_round),
             }
+        }
 
-            let value = Self::env().transferred_value();
+        fn _emit_player_joined_event(&self, player: AccountId) {
+            Self::env().emit_event(PlayerJoined {
+                game_address: Self::env().account_id(),
+                player,
+            });
+        }
 
-            current_round.player_contributions.push((caller, value));
-            current_round.player_commits.push((caller, commitment));
-            current_round.total_contribution += value;
+        fn _emit_game_started_event(&self) {
+            Self::env().emit_event(GameStarted {
+                game_address: Self::env().account_id(),
+                players: self.game.players.clone(),
+            });
+        }
 
+        fn _emit_player_committed_event(&self, player: AccountId, commitment: Hash) {
             Self::env().emit_event(PlayerCommitted {
                 game_address: Self::env().account_id(),
-                player: caller,
+                player,
                 commitment,
             });
-
-            // check if all players have committed
-            if current_round.player_commits.len() == self.players.len() {
-                Self::env().emit_event(AllPlayersCommitted {
-                    game_address: Self::env().account_id(),
-                    commits: current_round.player_commits.clone(),
-                })
-            }
-
-            Ok(())
         }
 
-        #[ink(message, payable)]
-        fn reveal_round(&mut self, reveal: (u128, u128)) -> Result<(), GameError> {
-            if reveal.0 > 2 {
-                return Err(GameError::InvalidChoice);
-            }
-
-            let caller = Self::env().caller();
-            let data = [reveal.0.to_le_bytes(), reveal.1.to_le_bytes()].concat();
-            let mut output = <Blake2x256 as HashOutput>::Type::default(); // 256 bit buffer
-            ink_env::hash_bytes::<Blake2x256>(&data, &mut output);
-            let current_round = self.current_round.as_mut().unwrap();
-
-            let player_commitment = current_round
-                .player_commits
-                .iter()
-                .find(|(c, _)| c == &caller);
-
-            if let Some(c) = player_commitment {
-                if c.1 != output.into() {
-                    return Err(GameError::InvalidReveal);
-                }
-            } else {
-                return Err(GameError::CommitmentNotFound);
-            }
-
-            current_round.player_reveals.push((caller, reveal));
+        fn _emit_all_players_committed_event(&self, round: &GameRound) {
+            Self::env().emit_event(AllPlayersCommitted {
+                game_address: Self::env().account_id(),
+                commits: round.player_commits.clone(),
+            });
+        }
 
+        fn _emit_player_revealed_event(&self, player: AccountId, reveal: (u128, u128)) {
             Self::env().emit_event(PlayerRevealed {
                 game_address: Self::env().account_id(),
-                player: caller,
+                player,
                 reveal,
             });
-
-            Ok(())
         }
 
-        #[ink(message, payable)]
-        fn complete_round(&mut self) -> Result<(), GameError> {
-            let current_round = self.current_round.as_mut().unwrap();
-
-            if current_round.player_reveals.len() != self.players.len() {
-                return Err(GameError::NotAllPlayersRevealed);
-            };
-
-            if current_round.status != RoundStatus::OnGoing {
-                return Err(GameError::InvalidRoundState);
-            };
-
-            let player1 = current_round.player_reveals[0];
-            let player2 = current_round.player_reveals[1];
-
-            let rewards = current_round.total_contribution;
-
-            let mut winners = Vec::new();
-            let score = (player1.1 .0 - player2.1 .0) % 3;
-
-            match score {
-                1 => {
-                    Self::env()
-                        .transfer(player1.0, rewards)
-                        .map_err(|_| GameError::FailedToIssueWinnerRewards)?;
-                    winners.push((player1.0, rewards))
-                }
-                2 => {
-                    Self::env()
-                        .transfer(player2.0, rewards)
-                        .map_err(|_| GameError::FailedToIssueWinnerRewards)?;
-                    winners.push((player2.0, rewards))
-                }
-                0 => {
-                    self.next_round_id += 1;
-                    // send event empty vector of winners
-                    return Ok(());
-                }
-
-                _ => return Err(GameError::FailedToCloseRound),
-            }
-
-            current_round.status = RoundStatus::Ended;
-
+        fn _emit_round_completed_event(&self, round: &GameRound, payouts: &[(AccountId, u128)]) {
             Self::env().emit_event(RoundEnded {
                 game_address: Self::env().account_id(),
-                winners,
-                round_id: self.next_round_id,
-                total_contribution: rewards,
+                winners: payouts.to_vec(),
+                round_id: round.id,
+                total_contribution: round.total_contribution,
+                protocol_fee: round.protocol_fee,
             });
+        }
 
-            Ok(())
+        fn _emit_player_left_event(&self, player: AccountId) {
+            Self::env().emit_event(PlayerLeft {
+                game_address: Self::env().account_id(),
+                player,
+            });
         }
 
-        #[ink(message, payable)]
-        fn end_game(&mut self) -> Result<(), GameError> {
-            let current_round = self.current_round.as_mut().unwrap();
+        fn _emit_game_cancelled_event(&self) {
+            Self::env().emit_event(GameCancelled {
+                game_address: Self::env().account_id(),
+            });
+        }
 
-            if self.status != GameStatus::OnGoing {
-                return Err(GameError::InvalidGameState);
-            }
+        fn _emit_game_settled_event(&self, transfers: &[(AccountId, u128)]) {
+            Self::env().emit_event(GameSettled {
+                game_address: Self::env().account_id(),
+                transfers: transfers.to_vec(),
+            });
+        }
 
-            if current_round.status != RoundStatus::Ended {
-                return Err(GameError::InvalidRoundState);
-            }
+        fn _emit_router_notification_failed_event(&self, router: AccountId, callback: [u8; 4]) {
+            Self::env().emit_event(RouterNotificationFailed {
+                game_address: Self::env().account_id(),
+                router,
+                callback,
+            });
+        }
 
-            self.status = GameStatus::Ended;
+        fn _emit_claimed_event(&self, player: AccountId, amount: u128) {
+            Self::env().emit_event(BalanceClaimed {
+                game_address: Self::env().account_id(),
+                player,
+                amount,
+            });
+        }
 
-            Self::env().emit_event(GameEnded {
+        fn _emit_player_penalized_event(&self, round_id: u8, player: AccountId, penalty: u128) {
+            Self::env().emit_event(PlayerPenalized {
                 game_address: Self::env().account_id(),
-                rounds_played: self.next_round_id,
+                player,
+                round_id,
+                penalty,
             });
+        }
 
-            Self::env().terminate_contract(self.creator);
+        fn _emit_game_ended_event(&self) {
+            Self::env().emit_event(GameEnded {
+                game_address: Self::env().account_id(),
+                rounds_played: self.game.next_round_id,
+            });
         }
     }
 
@@ -443,12 +868,15 @@ pub mod rock_paper_scissors {
     mod tests {
         /// Imports all the definitions from the outer scope so we can use them here.
         use super::*;
+        use game_theory::logics::traits::types::{
+            ActionRatios, ConfigError, NonRevealPolicy, PayoffFormula,
+        };
 
         /// We test if the default constructor does its job.
         #[ink::test]
         fn default_works() {
             let rock_paper_scissors = RockPaperScissors::default();
-            assert_eq!(rock_paper_scissors.players, vec![]);
+            assert_eq!(rock_paper_scissors.game.players, vec![]);
             assert_eq!(rock_paper_scissors.get_current_round(), None)
         }
 
@@ -465,13 +893,38 @@ pub mod rock_paper_scissors {
                 round_timeout: None,
                 max_rounds: None,
                 join_fee: None,
+                join_deadline: None,
                 is_rounds_based: false,
-            });
+                non_reveal_policy: NonRevealPolicy::ForfeitToPot,
+                tie_policy: TiePolicy::Replay,
+                elimination: false,
+                move_count: None,
+                router: None,
+                creator: None,
+                ratings: None,
+                protocol_fee_bps: 0,
+                payoff_formula: PayoffFormula::Linear,
+                action_ratios: ActionRatios::default(),
+            })
+            .unwrap();
 
-            assert_eq!(rock_paper_scissors.players, vec![]);
+            assert_eq!(rock_paper_scissors.game.players, vec![]);
             assert_eq!(rock_paper_scissors.get_current_round(), None);
         }
 
+        /// Rounds can't be settled with a single player.
+        #[ink::test]
+        fn new_rejects_single_player() {
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.min_players = 1;
+            assert_eq!(
+                RockPaperScissors::new(configs).err(),
+                Some(GameError::InvalidConfig(
+                    ConfigError::UnsupportedPlayerCount
+                ))
+            );
+        }
+
         /// A new player can join the game.
         #[ink::test]
         fn player_can_join() {
@@ -539,18 +992,551 @@ pub mod rock_paper_scissors {
             let result = rock_paper_scissors.start_game();
             assert_eq!(result, Ok(()));
 
-            let alice_data = [0_u128.to_le_bytes(), 69_u128.to_le_bytes()].concat();
-            let mut commitment = <Blake2x256 as HashOutput>::Type::default();
-            ink_env::hash_bytes::<Blake2x256>(&alice_data, &mut commitment);
+            let commitment = rock_paper_scissors
+                .hash_commitment(accounts.bob, 0, 69)
+                .unwrap();
             ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(
-                rock_paper_scissors.configs.min_round_contribution.unwrap(),
+                rock_paper_scissors
+                    .game
+                    .configs
+                    .min_round_contribution
+                    .unwrap(),
             );
 
-            let result = rock_paper_scissors.play_round(commitment.into());
+            let result = rock_paper_scissors.play_round(commitment);
 
             assert_eq!(result, Ok(()));
         }
 
+        #[ink::test]
+        fn expired_round_awards_pot_to_revealing_player() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.round_timeout = Some(1);
+            let mut rock_paper_scissors = RockPaperScissors::new(configs).unwrap();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert!(rock_paper_scissors.join(accounts.alice).is_ok());
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert!(rock_paper_scissors.join(accounts.bob).is_ok());
+            assert_eq!(rock_paper_scissors.start_game(), Ok(()));
+
+            let alice_commitment = rock_paper_scissors
+                .hash_commitment(accounts.alice, 0, 69)
+                .unwrap();
+            let bob_commitment = rock_paper_scissors
+                .hash_commitment(accounts.bob, 1, 42)
+                .unwrap();
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1);
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(rock_paper_scissors.play_round(alice_commitment), Ok(()));
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(rock_paper_scissors.play_round(bob_commitment), Ok(()));
+
+            // only alice reveals, bob never shows up
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(rock_paper_scissors.reveal_round((0, 69)), Ok(()));
+            assert_eq!(
+                rock_paper_scissors.close_expired_round(),
+                Err(GameError::RoundNotExpired)
+            );
+
+            for _ in 0..3 {
+                ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            }
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(rock_paper_scissors.close_expired_round(), Ok(()));
+            assert_eq!(
+                rock_paper_scissors
+                    .game
+                    .current_round
+                    .as_ref()
+                    .unwrap()
+                    .status,
+                RoundStatus::Ended
+            );
+            // alice can claim the whole pot
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.alice), 2);
+        }
+
+        /// Joins the players and starts the game.
+        fn start_game_with(rock_paper_scissors: &mut RockPaperScissors, players: &[AccountId]) {
+            for player in players {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(*player);
+                assert!(rock_paper_scissors.join(*player).is_ok());
+            }
+            assert_eq!(rock_paper_scissors.start_game(), Ok(()));
+        }
+
+        /// Joins alice and bob and starts the game.
+        fn start_two_player_game(rock_paper_scissors: &mut RockPaperScissors) {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            start_game_with(rock_paper_scissors, &[accounts.alice, accounts.bob]);
+        }
+
+        /// Plays a full round where each player stakes and reveals a choice, in the given order.
+        fn play_staked_round(
+            rock_paper_scissors: &mut RockPaperScissors,
+            reveals: &[(AccountId, u128, u128)],
+        ) {
+            for (player, choice, stake) in reveals {
+                let commitment = rock_paper_scissors
+                    .hash_commitment(*player, *choice, 69)
+                    .unwrap();
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(*player);
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(*stake);
+                assert_eq!(rock_paper_scissors.play_round(commitment), Ok(()));
+            }
+            for (player, choice, _) in reveals {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(*player);
+                assert_eq!(rock_paper_scissors.reveal_round((*choice, 69)), Ok(()));
+            }
+            assert_eq!(rock_paper_scissors.complete_round(), Ok(()));
+        }
+
+        /// Plays a full round where alice and bob reveal their choices in the given order.
+        fn play_round_with(
+            rock_paper_scissors: &mut RockPaperScissors,
+            reveals: [(AccountId, u128); 2],
+        ) {
+            play_staked_round(
+                rock_paper_scissors,
+                &reveals.map(|(player, choice)| (player, choice, 1)),
+            );
+        }
+
+        #[ink::test]
+        fn beats_is_cyclic() {
+            // paper beats rock, scissors beat paper, rock beats scissors
+            assert!(crate::beats(1, 0, 3));
+            assert!(crate::beats(2, 1, 3));
+            assert!(crate::beats(0, 2, 3));
+        }
+
+        /// Every move beats, and is beaten by, half of the other moves.
+        #[ink::test]
+        fn dominance_is_balanced() {
+            for moves in [3, 5, 7, 9] {
+                for choice in 0..moves {
+                    assert!(!crate::beats(choice, choice, moves));
+                    let mut wins = 0;
+                    for other in (0..moves).filter(|other| *other != choice) {
+                        assert_ne!(
+                            crate::beats(choice, other, moves),
+                            crate::beats(other, choice, moves)
+                        );
+                        if crate::beats(choice, other, moves) {
+                            wins += 1;
+                        }
+                    }
+                    assert_eq!(wins, (moves - 1) / 2);
+                }
+            }
+        }
+
+        /// Rock paper scissors lizard spock is played with 5 moves.
+        #[ink::test]
+        fn move_set_size_is_configurable() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.move_count = Some(4);
+            assert_eq!(
+                RockPaperScissors::new(configs.clone()).err(),
+                Some(GameError::InvalidConfig(ConfigError::UnsupportedMoveCount))
+            );
+
+            configs.move_count = Some(5);
+            let mut rock_paper_scissors = RockPaperScissors::new(configs).unwrap();
+            start_two_player_game(&mut rock_paper_scissors);
+
+            // a move outside of the move set can't be revealed
+            let commitment = rock_paper_scissors
+                .hash_commitment(accounts.alice, 5, 69)
+                .unwrap();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1);
+            assert_eq!(rock_paper_scissors.play_round(commitment), Ok(()));
+            assert_eq!(
+                rock_paper_scissors.reveal_round((5, 69)),
+                Err(GameError::InvalidChoice)
+            );
+        }
+
+        /// With 5 moves, a move beats the two moves right before it.
+        #[ink::test]
+        fn lizard_spock_round_is_decided() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.move_count = Some(5);
+            let mut rock_paper_scissors = RockPaperScissors::new(configs).unwrap();
+            start_two_player_game(&mut rock_paper_scissors);
+
+            // 4 beats 2 and 3, so it beats 2
+            play_round_with(
+                &mut rock_paper_scissors,
+                [(accounts.alice, 2), (accounts.bob, 4)],
+            );
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::Ended);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.bob), 2);
+        }
+
+        /// The outcome doesn't depend on the order of the reveals, nor underflows when the
+        /// second choice is the largest.
+        #[ink::test]
+        fn outcome_does_not_depend_on_reveal_order() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            for reveals in [
+                [(accounts.alice, 0), (accounts.bob, 2)],
+                [(accounts.bob, 2), (accounts.alice, 0)],
+            ] {
+                let mut rock_paper_scissors = RockPaperScissors::default();
+                start_two_player_game(&mut rock_paper_scissors);
+                play_round_with(&mut rock_paper_scissors, reveals);
+
+                // rock beats scissors
+                assert_eq!(rock_paper_scissors.get_claimable(accounts.alice), 2);
+                assert_eq!(rock_paper_scissors.get_claimable(accounts.bob), 0);
+                assert_eq!(rock_paper_scissors.get_status(), GameStatus::Ended);
+            }
+        }
+
+        /// A tie is replayed, and the winner of the next round takes both pots.
+        #[ink::test]
+        fn tie_is_replayed_with_carried_pot() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut rock_paper_scissors = RockPaperScissors::default();
+            start_two_player_game(&mut rock_paper_scissors);
+
+            play_round_with(
+                &mut rock_paper_scissors,
+                [(accounts.alice, 1), (accounts.bob, 1)],
+            );
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::OnGoing);
+            assert_eq!(rock_paper_scissors.get_carried_pot(), 2);
+            assert_eq!(rock_paper_scissors.get_current_round().unwrap().id, 2);
+
+            play_round_with(
+                &mut rock_paper_scissors,
+                [(accounts.alice, 0), (accounts.bob, 1)],
+            );
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::Ended);
+            assert_eq!(rock_paper_scissors.get_carried_pot(), 0);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.bob), 4);
+        }
+
+        /// With `TiePolicy::Refund`, a tie refunds each player and ends the game.
+        #[ink::test]
+        fn tie_can_be_refunded() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.tie_policy = TiePolicy::Refund;
+            let mut rock_paper_scissors = RockPaperScissors::new(configs).unwrap();
+            start_two_player_game(&mut rock_paper_scissors);
+
+            play_round_with(
+                &mut rock_paper_scissors,
+                [(accounts.alice, 2), (accounts.bob, 2)],
+            );
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::Ended);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.alice), 1);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.bob), 1);
+        }
+
+        /// The contributions refunded on a tie are not winnings, no protocol fee is taken
+        /// from them.
+        #[ink::test]
+        fn refunded_tie_has_no_winnings() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.tie_policy = TiePolicy::Refund;
+            let mut rock_paper_scissors = RockPaperScissors::new(configs).unwrap();
+            start_two_player_game(&mut rock_paper_scissors);
+
+            for player in [accounts.alice, accounts.bob] {
+                let commitment = rock_paper_scissors.hash_commitment(player, 2, 69).unwrap();
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(player);
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1);
+                assert_eq!(rock_paper_scissors.play_round(commitment), Ok(()));
+            }
+            for player in [accounts.alice, accounts.bob] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(player);
+                assert_eq!(rock_paper_scissors.reveal_round((2, 69)), Ok(()));
+            }
+
+            let mut round = rock_paper_scissors.game.current_round.clone().unwrap();
+            round.status = RoundStatus::Ended;
+            assert_eq!(
+                rock_paper_scissors._settle_round(&round),
+                Ok(RoundPayouts::refunds(vec![
+                    (accounts.alice, 1),
+                    (accounts.bob, 1)
+                ]))
+            );
+        }
+
+        /// With two symbols revealed, the winners split the pot pro rata to their stake.
+        #[ink::test]
+        fn winners_split_pot_pro_rata() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.max_players = 3;
+            configs.min_players = 3;
+            let mut rock_paper_scissors = RockPaperScissors::new(configs).unwrap();
+            start_game_with(
+                &mut rock_paper_scissors,
+                &[accounts.alice, accounts.bob, accounts.charlie],
+            );
+
+            play_staked_round(
+                &mut rock_paper_scissors,
+                &[
+                    (accounts.alice, 0, 1),
+                    (accounts.bob, 0, 3),
+                    (accounts.charlie, 2, 4),
+                ],
+            );
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::Ended);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.alice), 2);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.bob), 6);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.charlie), 0);
+        }
+
+        /// The remainder of the split goes to the same winner whatever the reveal order.
+        #[ink::test]
+        fn split_remainder_does_not_depend_on_reveal_order() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.max_players = 3;
+            configs.min_players = 3;
+            let mut rock_paper_scissors = RockPaperScissors::new(configs).unwrap();
+            start_game_with(
+                &mut rock_paper_scissors,
+                &[accounts.alice, accounts.bob, accounts.charlie],
+            );
+
+            play_staked_round(
+                &mut rock_paper_scissors,
+                &[
+                    (accounts.bob, 0, 1),
+                    (accounts.alice, 0, 1),
+                    (accounts.charlie, 2, 1),
+                ],
+            );
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::Ended);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.alice), 2);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.bob), 1);
+        }
+
+        /// All three symbols revealed is a tie.
+        #[ink::test]
+        fn all_symbols_is_a_tie() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.max_players = 3;
+            configs.min_players = 3;
+            let mut rock_paper_scissors = RockPaperScissors::new(configs).unwrap();
+            start_game_with(
+                &mut rock_paper_scissors,
+                &[accounts.alice, accounts.bob, accounts.charlie],
+            );
+
+            play_staked_round(
+                &mut rock_paper_scissors,
+                &[
+                    (accounts.alice, 0, 1),
+                    (accounts.bob, 1, 1),
+                    (accounts.charlie, 2, 1),
+                ],
+            );
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::OnGoing);
+            assert_eq!(rock_paper_scissors.get_carried_pot(), 3);
+        }
+
+        /// With elimination, the losers leave the game until the last player takes the pot.
+        #[ink::test]
+        fn losers_are_eliminated_until_one_player_remains() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.max_players = 3;
+            configs.min_players = 3;
+            configs.elimination = true;
+            let mut rock_paper_scissors = RockPaperScissors::new(configs).unwrap();
+            start_game_with(
+                &mut rock_paper_scissors,
+                &[accounts.alice, accounts.bob, accounts.charlie],
+            );
+
+            play_staked_round(
+                &mut rock_paper_scissors,
+                &[
+                    (accounts.alice, 0, 1),
+                    (accounts.bob, 0, 1),
+                    (accounts.charlie, 2, 1),
+                ],
+            );
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::OnGoing);
+            assert_eq!(
+                rock_paper_scissors.get_players(),
+                vec![accounts.alice, accounts.bob]
+            );
+            assert_eq!(rock_paper_scissors.get_carried_pot(), 3);
+
+            play_round_with(
+                &mut rock_paper_scissors,
+                [(accounts.alice, 1), (accounts.bob, 0)],
+            );
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::Ended);
+            assert_eq!(rock_paper_scissors.get_players(), vec![accounts.alice]);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.alice), 5);
+        }
+
+        /// A best-of-3 series is played until a player wins 2 rounds, ties aren't scored.
+        #[ink::test]
+        fn series_is_won_by_the_first_player_to_win_most_rounds() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.is_rounds_based = true;
+            configs.max_rounds = Some(3);
+            let mut rock_paper_scissors = RockPaperScissors::new(configs).unwrap();
+            start_two_player_game(&mut rock_paper_scissors);
+
+            for (alice, bob) in [(0, 2), (1, 1), (0, 1)] {
+                play_round_with(
+                    &mut rock_paper_scissors,
+                    [(accounts.alice, alice), (accounts.bob, bob)],
+                );
+                assert_eq!(rock_paper_scissors.get_status(), GameStatus::OnGoing);
+            }
+            assert_eq!(
+                rock_paper_scissors.get_score(),
+                vec![(accounts.alice, 1), (accounts.bob, 1)]
+            );
+
+            play_round_with(
+                &mut rock_paper_scissors,
+                [(accounts.alice, 2), (accounts.bob, 1)],
+            );
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::Ended);
+            assert_eq!(
+                rock_paper_scissors.get_score(),
+                vec![(accounts.alice, 2), (accounts.bob, 1)]
+            );
+            // the pot of every round is escrowed until `end_game`
+            assert_eq!(rock_paper_scissors.get_carried_pot(), 8);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.alice), 0);
+
+            // the winner has to claim the pot before the game can be terminated
+            assert_eq!(rock_paper_scissors.end_game(), Ok(()));
+            assert_eq!(rock_paper_scissors.get_carried_pot(), 0);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.alice), 8);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.bob), 0);
+            assert_eq!(rock_paper_scissors.end_game(), Ok(()));
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.alice), 8);
+        }
+
+        /// Starts a best-of-3 series between alice and bob, where alice wins the first round
+        /// staking 1 against 3.
+        fn start_series() -> RockPaperScissors {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.is_rounds_based = true;
+            configs.max_rounds = Some(3);
+            let mut rock_paper_scissors = RockPaperScissors::new(configs).unwrap();
+            start_two_player_game(&mut rock_paper_scissors);
+
+            play_staked_round(
+                &mut rock_paper_scissors,
+                &[(accounts.alice, 0, 1), (accounts.bob, 2, 3)],
+            );
+            assert_eq!(rock_paper_scissors.get_carried_pot(), 4);
+            rock_paper_scissors
+        }
+
+        /// When a round of an unfinished series is abandoned, the pot goes back to the players
+        /// pro rata to their stakes rather than to the creator.
+        #[ink::test]
+        fn abandoned_series_refunds_the_pot() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut rock_paper_scissors = start_series();
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(rock_paper_scissors.force_complete_round(), Ok(()));
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::Ended);
+            assert_eq!(rock_paper_scissors.get_carried_pot(), 0);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.alice), 1);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.bob), 3);
+        }
+
+        /// Forcing the end of an unfinished series refunds the pot, including the pot of the
+        /// tied rounds.
+        #[ink::test]
+        fn force_end_game_refunds_the_pot_of_an_unfinished_series() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut rock_paper_scissors = start_series();
+
+            // both reveal rock, the round is tied
+            for player in [accounts.alice, accounts.bob] {
+                let commitment = rock_paper_scissors.hash_commitment(player, 0, 69).unwrap();
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(player);
+                ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(2);
+                assert_eq!(rock_paper_scissors.play_round(commitment), Ok(()));
+            }
+            for player in [accounts.alice, accounts.bob] {
+                ink::env::test::set_caller::<ink::env::DefaultEnvironment>(player);
+                assert_eq!(rock_paper_scissors.reveal_round((0, 69)), Ok(()));
+            }
+
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(rock_paper_scissors.force_end_game(), Ok(()));
+            assert_eq!(rock_paper_scissors.get_status(), GameStatus::Ended);
+            assert_eq!(rock_paper_scissors.get_carried_pot(), 0);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.alice), 3);
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.bob), 5);
+        }
+
+        /// The value paid when adding a player, e.g. a stake forwarded by the router, is held
+        /// as their deposit.
+        #[ink::test]
+        fn added_players_deposits_are_held() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut rock_paper_scissors = RockPaperScissors::default();
+
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+            assert_eq!(rock_paper_scissors.add_player_to_game(accounts.bob), Ok(1));
+            assert_eq!(
+                rock_paper_scissors.add_player_to_game(accounts.charlie),
+                Ok(2)
+            );
+            assert_eq!(
+                rock_paper_scissors.game.join_fees,
+                vec![(accounts.bob, 10), (accounts.charlie, 10)]
+            );
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.bob), 0);
+
+            // the deposit is refunded like a join fee
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(rock_paper_scissors.leave(), Ok(1));
+            assert_eq!(rock_paper_scissors.get_claimable(accounts.bob), 10);
+            assert_eq!(
+                rock_paper_scissors.game.join_fees,
+                vec![(accounts.charlie, 10)]
+            );
+        }
+
+        #[ink::test]
+        fn series_requires_max_rounds() {
+            let mut configs = RockPaperScissors::default().game.configs;
+            configs.is_rounds_based = true;
+            assert_eq!(
+                RockPaperScissors::new(configs).err(),
+                Some(GameError::InvalidConfig(ConfigError::UnsupportedRoundCount))
+            );
+        }
+
         #[ink::test]
         fn only_contract_creator_can_emit_start_event() {
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
@@ -1,57 +1,342 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![feature(min_specialization)]
 
 #[openbrush::contract]
 mod router {
-    use dictator::DictatorRef;
+    use game_theory::logics::traits::router::*;
+    use game_theory::logics::traits::types::{GameConfigs, GameError, GameStatus, MAX_FEE_BPS};
+    use ink::env::call::{build_call, build_create, Call, ExecutionInput, FromAccountId, Selector};
+    use ink::prelude::{string::String, vec::Vec};
     use ink::storage::Mapping;
-    use public_good::PublicGoodRef;
-    use rock_paper_scissors::RockPaperScissorsRef;
+    use ink::ToAccountId;
+    use openbrush::contracts::access_control::{
+        extensions::enumerable::*, only_role, DEFAULT_ADMIN_ROLE,
+    };
+    use openbrush::modifiers;
+    use openbrush::traits::{Storage, ZERO_ADDRESS};
 
-    /// Game types.
-    #[derive(scale::Encode, scale::Decode, PartialEq, Eq, Clone, Copy, Debug)]
+    /// Access control roles, the owner holds `DEFAULT_ADMIN_ROLE` and can grant the others.
+    /// Can register game types, add code versions and upgrade games
+    pub const GAME_MANAGER: RoleType = ink::selector_id!("GAME_MANAGER");
+    /// Can manage the config templates
+    pub const TEMPLATE_MANAGER: RoleType = ink::selector_id!("TEMPLATE_MANAGER");
+    /// Can collect the fees earned by the router
+    pub const FEE_COLLECTOR: RoleType = ink::selector_id!("FEE_COLLECTOR");
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: Option<AccountId>,
+        #[ink(topic)]
+        new_owner: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct MatchCreated {
+        #[ink(topic)]
+        game_type: GameTypeId,
+        #[ink(topic)]
+        game_address: AccountId,
+        stake: Balance,
+        players: Vec<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct FeesWithdrawn {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct SurplusWithdrawn {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// Identifies a type of game registered in the router.
+    pub type GameTypeId = u32;
+
+    /// Returns the positions of the page of up to `limit` items skipping the first `offset`,
+    /// out of `count` items.
+    fn page(count: u32, offset: u32, limit: u32) -> core::ops::Range<u32> {
+        offset.min(count)..offset.saturating_add(limit).min(count)
+    }
+
+    /// A type of game which the router can instantiate.
+    ///
+    /// The game's contract must provide a payable constructor taking a single `GameConfigs`
+    /// argument and returning `Result<Self, GameError>`, as every game built on the
+    /// `Basic`/`Lifecycle` traits does with `new`. To be upgraded through the router,
+    /// the game must also implement the `Upgradeable` trait.
+    #[derive(scale::Encode, scale::Decode, PartialEq, Eq, Clone, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct GameType {
+        /// A human readable name, e.g. "Public Good"
+        pub name: String,
+        /// The selector of the `new(configs: GameConfigs)` constructor
+        pub constructor_selector: [u8; 4],
+        /// The configs used when `new_game` is called without configs
+        pub default_configs: GameConfigs,
+        /// The protocol fee, in basis points, taken from the payouts of the games of this type
+        pub fee_bps: u16,
+    }
+
+    /// A version of the code of a game type.
+    #[derive(scale::Encode, scale::Decode, PartialEq, Eq, Clone, Debug)]
     #[cfg_attr(
         feature = "std",
         derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
     )]
-    pub enum Game {
-        RockPaperScissors,
-        PublicGood,
-        Dictator,
+    pub struct GameVersion {
+        /// The version number, starting at 1
+        pub version: u32,
+        pub code_hash: Hash,
+        /// The block at which the version was added
+        pub set_at: BlockNumber,
+        /// Deprecated versions can't be used for new games or upgrades
+        pub deprecated: bool,
     }
 
     /// Router errors.
     #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
     pub enum RouterError {
+        /// Wrapper for errors from the access-control openbrush contract, e.g. missing role
+        AccessControlError(AccessControlError),
+        /// Only the owner of the router can do this
+        CallerIsNotOwner,
+        /// The router has no owner anymore
+        OwnershipRenounced,
+        /// Ownership can't be transferred to the zero address
+        InvalidNewOwner,
         FailedToInstantiateGame,
-        HashNotFoundForGame,
-        MustBeOwnerToSetGameHash,
+        /// The configs are not valid for the game type
+        InvalidGameConfig(GameError),
+        /// No game type is registered with this id
+        GameTypeNotFound,
+        /// The game type has no such version
+        VersionNotFound,
+        /// The version is deprecated
+        VersionDeprecated,
+        /// No game is registered with this id
+        GameNotFound,
+        /// The game rejected the upgrade
+        FailedToUpgradeGame,
+        /// No config template is stored under this name
+        TemplateNotFound,
+        /// The fee can't exceed `MAX_FEE_BPS`
+        InvalidFee,
+        /// The router hasn't collected any fee
+        NoFeesToWithdraw,
+        FailedToWithdrawFees,
+        /// The router holds nothing besides the queued stakes and the accrued fees
+        NoSurplusToWithdraw,
+        FailedToWithdrawSurplus,
+        /// The game rejected the forced completion of its round or its end
+        FailedToForceGame,
+        /// The value paid to `enqueue` must match the stake
+        StakeMismatch,
+        /// The player is already waiting in this queue
+        AlreadyQueued,
+        /// The player isn't waiting in this queue
+        NotQueued,
+        FailedToRefundStake,
+        /// The game failed to add a matched player
+        FailedToAddPlayer,
+    }
+
+    impl From<AccessControlError> for RouterError {
+        fn from(error: AccessControlError) -> Self {
+            RouterError::AccessControlError(error)
+        }
+    }
+
+    /// A game instantiated by the router.
+    #[derive(scale::Encode, scale::Decode, PartialEq, Eq, Clone, Debug)]
+    #[cfg_attr(
+        feature = "std",
+        derive(ink::storage::traits::StorageLayout, scale_info::TypeInfo)
+    )]
+    pub struct GameRecord {
+        /// The id of the game within the router, starting at 1
+        pub id: u32,
+        pub game_type: GameTypeId,
+        /// The version of the game type's code the game runs
+        pub version: u32,
+        /// The address of the game's contract
+        pub address: AccountId,
+        /// The account which called `new_game`, or the router for the matches created by `enqueue`
+        pub creator: AccountId,
+        /// The block at which the game was instantiated
+        pub created_at: BlockNumber,
+        /// The code hash the game runs
+        pub code_hash: Hash,
+        /// The status of the game, as last reported by the game
+        pub status: GameStatus,
+        /// The players of the game, reported by the game once it has started
+        pub players: Vec<AccountId>,
+        /// The number of rounds completed, as last reported by the game
+        pub rounds_completed: u32,
+        /// The protocol fees collected from the game, as reported by the game
+        pub fees_collected: Balance,
+    }
+
+    /// A reference to any game contract, used to instantiate the registered game types
+    /// without depending on their crates.
+    #[derive(scale::Encode, scale::Decode, Debug, Clone)]
+    pub struct GameRef {
+        account_id: AccountId,
+    }
+
+    impl ink::env::ContractEnv for GameRef {
+        type Env = Environment;
+    }
+
+    impl FromAccountId<Environment> for GameRef {
+        fn from_account_id(account_id: AccountId) -> Self {
+            Self { account_id }
+        }
+    }
+
+    impl ToAccountId<Environment> for GameRef {
+        fn to_account_id(&self) -> AccountId {
+            self.account_id
+        }
     }
 
     #[ink(storage)]
+    #[derive(Storage)]
     pub struct Router {
-        owner: AccountId,
-        game_hashes: Mapping<Game, Hash>,
+        #[storage_field]
+        access: access_control::Data<enumerable::Members>,
+        /// Every game type which can be instantiated, by id
+        game_types: Mapping<GameTypeId, GameType>,
+        /// The code versions of each game type, by game type and version number
+        game_versions: Mapping<(GameTypeId, u32), GameVersion>,
+        /// The number of versions of each game type, i.e. the latest version number
+        game_versions_count: Mapping<GameTypeId, u32>,
         games_count: u32,
+        /// Every game instantiated by the router, by id
+        games: Mapping<u32, GameRecord>,
+        /// The ids of the games instantiated by each account, by account and position
+        creator_games: Mapping<(AccountId, u32), u32>,
+        /// The number of games instantiated by each account
+        creator_games_count: Mapping<AccountId, u32>,
+        /// The id of each game instantiated by the router, by address
+        game_ids: Mapping<AccountId, u32>,
+        /// The ids of the games in each status, by status and position
+        status_games: Mapping<(GameStatus, u32), u32>,
+        /// The number of games in each status
+        status_games_count: Mapping<GameStatus, u32>,
+        /// The position of each game in the index of its status
+        status_positions: Mapping<u32, u32>,
+        /// The ids of the games each account played in, by account and position
+        player_games: Mapping<(AccountId, u32), u32>,
+        /// The number of games each account played in
+        player_games_count: Mapping<AccountId, u32>,
+        /// The players waiting for a match, by game type and stake
+        queues: Mapping<(GameTypeId, Balance), Vec<AccountId>>,
+        /// The sum of the stakes held for the players waiting in the queues
+        queued_stakes: Balance,
+        /// The protocol fees reported by the games and not withdrawn yet
+        accrued_fees: Balance,
+        /// Named configs, to launch standard games by name
+        templates: Mapping<String, (GameTypeId, GameConfigs)>,
+    }
+
+    impl AccessControl for Router {}
+
+    impl AccessControlEnumerable for Router {}
+
+    impl GameRegistry for Router {
+        #[ink(message)]
+        fn is_registered_game(&self, game: AccountId) -> bool {
+            self.game_ids.contains(game)
+        }
+    }
+
+    impl RouterCallbacks for Router {
+        #[ink(message)]
+        fn on_game_started(&mut self, players: Vec<AccountId>) -> Result<(), GameError> {
+            let mut record = self.caller_game()?;
+            for player in players.iter() {
+                let count = self.player_games_count.get(player).unwrap_or(0);
+                self.player_games.insert((*player, count), &record.id);
+                self.player_games_count.insert(player, &(count + 1));
+            }
+            record.players = players;
+            self.update_status(&mut record, GameStatus::OnGoing);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn on_round_completed(&mut self, round_id: u8, fee: Balance) -> Result<(), GameError> {
+            let mut record = self.caller_game()?;
+            // a round can be reported again when more fees are collected from it
+            record.rounds_completed = record.rounds_completed.max(u32::from(round_id));
+            record.fees_collected += fee;
+            self.accrued_fees += fee;
+            self.games.insert(record.id, &record);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn on_game_ended(&mut self, status: GameStatus) -> Result<(), GameError> {
+            let mut record = self.caller_game()?;
+            self.update_status(&mut record, status);
+            Ok(())
+        }
     }
 
     impl Router {
         /// Helper method to ensure that the caller is the contract owner.
         fn ensure_owner(&self) -> Result<(), RouterError> {
-            if self.env().caller() != self.owner {
-                return Err(RouterError::MustBeOwnerToSetGameHash);
+            if self.has_role(DEFAULT_ADMIN_ROLE, self.env().caller()) {
+                Ok(())
+            } else if self.get_owner().is_some() {
+                Err(RouterError::CallerIsNotOwner)
+            } else {
+                Err(RouterError::OwnershipRenounced)
             }
-
-            Ok(())
         }
 
         #[ink(constructor)]
         pub fn new() -> Self {
-            Self {
-                owner: Self::env().caller(),
-                game_hashes: Mapping::new(),
+            let caller = Self::env().caller();
+            let mut instance = Self {
+                access: Default::default(),
+                game_types: Mapping::new(),
+                game_versions: Mapping::new(),
+                game_versions_count: Mapping::new(),
                 games_count: 0,
+                games: Mapping::new(),
+                creator_games: Mapping::new(),
+                creator_games_count: Mapping::new(),
+                game_ids: Mapping::new(),
+                status_games: Mapping::new(),
+                status_games_count: Mapping::new(),
+                status_positions: Mapping::new(),
+                player_games: Mapping::new(),
+                player_games_count: Mapping::new(),
+                queues: Mapping::new(),
+                queued_stakes: 0,
+                accrued_fees: 0,
+                templates: Mapping::new(),
+            };
+
+            instance._init_with_admin(caller);
+            for role in [GAME_MANAGER, TEMPLATE_MANAGER, FEE_COLLECTOR] {
+                instance
+                    .grant_role(role, caller)
+                    .expect("Should grant the router roles");
             }
+
+            instance
         }
 
         #[ink(constructor)]
@@ -59,58 +344,1451 @@ mod router {
             Self::new()
         }
 
+        /// Get the owner of the router, i.e. the account holding `DEFAULT_ADMIN_ROLE`, `None`
+        /// once ownership is renounced.
         #[ink(message)]
-        pub fn get_owner(&self) -> AccountId {
-            self.owner
+        pub fn get_owner(&self) -> Option<AccountId> {
+            self.get_role_member(DEFAULT_ADMIN_ROLE, 0)
         }
 
+        /// Transfers the ownership (i.e. `DEFAULT_ADMIN_ROLE`) of the router to `new_owner`.
+        ///
+        /// The other roles held by the previous owner are kept, and can be revoked by the new owner.
         #[ink(message)]
-        pub fn get_game_hash(&self, which: Game) -> Result<Hash, RouterError> {
-            self.game_hashes
-                .get(&which)
-                .ok_or(RouterError::HashNotFoundForGame)
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), RouterError> {
+            self.ensure_owner()?;
+            if new_owner == ZERO_ADDRESS.into() {
+                return Err(RouterError::InvalidNewOwner);
+            }
+
+            let previous_owner = self.env().caller();
+            self.grant_role(DEFAULT_ADMIN_ROLE, new_owner)?;
+            self.renounce_role(DEFAULT_ADMIN_ROLE, previous_owner)?;
+
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner: Some(previous_owner),
+                new_owner: Some(new_owner),
+            });
+
+            Ok(())
         }
 
-        #[ink(message, payable)]
-        pub fn set_game_hash(&mut self, which: Game, hash: Hash) -> Result<(), RouterError> {
+        /// Gives up the ownership of the router, once nobody holds `DEFAULT_ADMIN_ROLE` nobody can
+        /// grant or revoke roles anymore.
+        #[ink(message)]
+        pub fn renounce_ownership(&mut self) -> Result<(), RouterError> {
             self.ensure_owner()?;
-            self.game_hashes.insert(which, &hash);
+
+            let previous_owner = self.env().caller();
+            self.renounce_role(DEFAULT_ADMIN_ROLE, previous_owner)?;
+
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner: Some(previous_owner),
+                new_owner: None,
+            });
+
             Ok(())
         }
 
-        /// A methods that adds a game and instantiates its contract.
+        /// Get a registered game type.
+        #[ink(message)]
+        pub fn get_game_type(&self, which: GameTypeId) -> Result<GameType, RouterError> {
+            self.game_types
+                .get(which)
+                .ok_or(RouterError::GameTypeNotFound)
+        }
+
+        /// Registers (or replaces) a game type.
+        ///
+        /// The game type can be used by `new_game` once a version of its code is added
+        /// with `set_game_hash`.
+        #[ink(message)]
+        #[modifiers(only_role(GAME_MANAGER))]
+        pub fn register_game_type(
+            &mut self,
+            which: GameTypeId,
+            game_type: GameType,
+        ) -> Result<(), RouterError> {
+            game_type
+                .default_configs
+                .validate()
+                .map_err(RouterError::InvalidGameConfig)?;
+            if game_type.fee_bps > MAX_FEE_BPS {
+                return Err(RouterError::InvalidFee);
+            }
+            self.game_types.insert(which, &game_type);
+            Ok(())
+        }
+
+        /// Sets the protocol fee, in basis points, of a game type.
+        ///
+        /// Only the games created afterwards are affected.
+        #[ink(message)]
+        #[modifiers(only_role(GAME_MANAGER))]
+        pub fn set_game_fee(&mut self, which: GameTypeId, fee_bps: u16) -> Result<(), RouterError> {
+            if fee_bps > MAX_FEE_BPS {
+                return Err(RouterError::InvalidFee);
+            }
+            let mut game_type = self.get_game_type(which)?;
+            game_type.fee_bps = fee_bps;
+            self.game_types.insert(which, &game_type);
+            Ok(())
+        }
+
+        /// Get the protocol fees reported by the games of the router and not withdrawn yet.
+        #[ink(message)]
+        pub fn get_accrued_fees(&self) -> Balance {
+            self.accrued_fees
+        }
+
+        /// Transfers every accrued protocol fee to `to`.
+        ///
+        /// Returns the amount withdrawn.
+        #[ink(message)]
+        #[modifiers(only_role(FEE_COLLECTOR))]
+        pub fn withdraw_fees(&mut self, to: AccountId) -> Result<Balance, RouterError> {
+            let amount = self.accrued_fees;
+            if amount == 0 {
+                return Err(RouterError::NoFeesToWithdraw);
+            }
+
+            self.accrued_fees = 0;
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| RouterError::FailedToWithdrawFees)?;
+            self.env().emit_event(FeesWithdrawn { to, amount });
+
+            Ok(amount)
+        }
+
+        /// Get what the router holds besides the queued stakes and the accrued fees, e.g. the
+        /// balance left by the matches created by `enqueue` when they are terminated.
+        #[ink(message)]
+        pub fn get_surplus(&self) -> Balance {
+            self.env()
+                .balance()
+                .saturating_sub(self.env().minimum_balance())
+                .saturating_sub(self.queued_stakes)
+                .saturating_sub(self.accrued_fees)
+        }
+
+        /// Transfers the surplus of the router (see `get_surplus`) to `to`.
+        ///
+        /// Returns the amount withdrawn.
+        #[ink(message)]
+        #[modifiers(only_role(FEE_COLLECTOR))]
+        pub fn withdraw_surplus(&mut self, to: AccountId) -> Result<Balance, RouterError> {
+            let amount = self.get_surplus();
+            if amount == 0 {
+                return Err(RouterError::NoSurplusToWithdraw);
+            }
+
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| RouterError::FailedToWithdrawSurplus)?;
+            self.env().emit_event(SurplusWithdrawn { to, amount });
+
+            Ok(amount)
+        }
+
+        /// Removes a game type, existing games of this type are not affected.
+        #[ink(message)]
+        #[modifiers(only_role(GAME_MANAGER))]
+        pub fn unregister_game_type(&mut self, which: GameTypeId) -> Result<(), RouterError> {
+            self.get_game_type(which)?;
+            self.game_types.remove(which);
+            Ok(())
+        }
+
+        /// Get the code hash of the latest version of a game type which isn't deprecated.
+        #[ink(message)]
+        pub fn get_game_hash(&self, which: GameTypeId) -> Result<Hash, RouterError> {
+            Ok(self.resolve_version(which, None)?.code_hash)
+        }
+
+        /// Adds a new version of the code of a game type, which becomes its latest version.
+        ///
+        /// Returns the version number.
         #[ink(message, payable)]
-        pub fn new_game(&mut self, which: Game) -> Result<(), RouterError> {
-            let game_hash = self.get_game_hash(which)?;
-
-            self.games_count += 1;
-
-            match which {
-                Game::RockPaperScissors => {
-                    RockPaperScissorsRef::default()
-                        .code_hash(game_hash)
-                        .endowment(self.env().transferred_value())
-                        .gas_limit(0)
-                        .salt_bytes(self.games_count.to_le_bytes())
-                        .instantiate();
-                }
-                Game::PublicGood => {
-                    PublicGoodRef::default()
-                        .code_hash(game_hash)
-                        .endowment(self.env().transferred_value())
-                        .gas_limit(0)
-                        .salt_bytes(self.games_count.to_le_bytes())
-                        .instantiate();
+        #[modifiers(only_role(GAME_MANAGER))]
+        pub fn set_game_hash(&mut self, which: GameTypeId, hash: Hash) -> Result<u32, RouterError> {
+            self.get_game_type(which)?;
+
+            let version = self.game_versions_count.get(which).unwrap_or(0) + 1;
+            self.game_versions.insert(
+                (which, version),
+                &GameVersion {
+                    version,
+                    code_hash: hash,
+                    set_at: self.env().block_number(),
+                    deprecated: false,
+                },
+            );
+            self.game_versions_count.insert(which, &version);
+
+            Ok(version)
+        }
+
+        /// Marks a version of a game type as deprecated (or not), games already running it are
+        /// not affected.
+        #[ink(message)]
+        #[modifiers(only_role(GAME_MANAGER))]
+        pub fn set_version_deprecated(
+            &mut self,
+            which: GameTypeId,
+            version: u32,
+            deprecated: bool,
+        ) -> Result<(), RouterError> {
+            let mut game_version = self
+                .get_game_version(which, version)
+                .ok_or(RouterError::VersionNotFound)?;
+            game_version.deprecated = deprecated;
+            self.game_versions.insert((which, version), &game_version);
+            Ok(())
+        }
+
+        /// Get a version of a game type.
+        #[ink(message)]
+        pub fn get_game_version(&self, which: GameTypeId, version: u32) -> Option<GameVersion> {
+            self.game_versions.get((which, version))
+        }
+
+        /// Get every version of a game type, oldest first.
+        #[ink(message)]
+        pub fn get_game_versions(&self, which: GameTypeId) -> Vec<GameVersion> {
+            let count = self.game_versions_count.get(which).unwrap_or(0);
+            (1..=count)
+                .filter_map(|version| self.get_game_version(which, version))
+                .collect()
+        }
+
+        /// Finds the version to use for a new game or an upgrade: the pinned `version` if any,
+        /// the latest version which isn't deprecated otherwise.
+        fn resolve_version(
+            &self,
+            which: GameTypeId,
+            version: Option<u32>,
+        ) -> Result<GameVersion, RouterError> {
+            match version {
+                Some(version) => {
+                    let game_version = self
+                        .get_game_version(which, version)
+                        .ok_or(RouterError::VersionNotFound)?;
+                    if game_version.deprecated {
+                        return Err(RouterError::VersionDeprecated);
+                    }
+                    Ok(game_version)
                 }
-                Game::Dictator => {
-                    DictatorRef::default()
-                        .code_hash(game_hash)
-                        .endowment(self.env().transferred_value())
-                        .gas_limit(0)
-                        .salt_bytes(self.games_count.to_le_bytes())
-                        .instantiate();
+                None => self
+                    .get_game_versions(which)
+                    .into_iter()
+                    .rev()
+                    .find(|game_version| !game_version.deprecated)
+                    .ok_or(RouterError::VersionNotFound),
+            }
+        }
+
+        /// Migrates a running game to another version of its game type's code, the latest
+        /// version which isn't deprecated if `version` is `None`.
+        ///
+        /// The game must have been instantiated by the router, which holds the game's `CREATOR`
+        /// role along with the account which created it.
+        #[ink(message)]
+        #[modifiers(only_role(GAME_MANAGER))]
+        pub fn upgrade_game(&mut self, id: u32, version: Option<u32>) -> Result<u32, RouterError> {
+            let mut record = self.get_game(id).ok_or(RouterError::GameNotFound)?;
+            let game_version = self.resolve_version(record.game_type, version)?;
+
+            build_call::<Environment>()
+                .call_type(Call::new().callee(record.address).gas_limit(0))
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                        "Upgradeable::set_code_hash"
+                    )))
+                    .push_arg(game_version.code_hash),
+                )
+                .returns::<Result<(), GameError>>()
+                .try_invoke()
+                .map_err(|_| RouterError::FailedToUpgradeGame)?
+                .map_err(|_| RouterError::FailedToUpgradeGame)?
+                .map_err(|_| RouterError::FailedToUpgradeGame)?;
+
+            record.version = game_version.version;
+            record.code_hash = game_version.code_hash;
+            self.games.insert(id, &record);
+
+            Ok(game_version.version)
+        }
+
+        /// Closes the current round of a game with whichever reveals were made, see
+        /// `Admin::force_complete_round`.
+        ///
+        /// The router is the creator of the matches created by `enqueue`, so that a player who
+        /// doesn't show up can't block them.
+        #[ink(message)]
+        #[modifiers(only_role(GAME_MANAGER))]
+        pub fn force_complete_round(&mut self, id: u32) -> Result<(), RouterError> {
+            self.force_game(id, ink::selector_bytes!("Admin::force_complete_round"))
+        }
+
+        /// Ends a game without playing its remaining rounds, see `Admin::force_end_game`.
+        #[ink(message)]
+        #[modifiers(only_role(GAME_MANAGER))]
+        pub fn force_end_game(&mut self, id: u32) -> Result<(), RouterError> {
+            self.force_game(id, ink::selector_bytes!("Admin::force_end_game"))
+        }
+
+        /// Calls one of the `Admin` messages forcing the progress of a game.
+        fn force_game(&self, id: u32, selector: [u8; 4]) -> Result<(), RouterError> {
+            let record = self.get_game(id).ok_or(RouterError::GameNotFound)?;
+
+            build_call::<Environment>()
+                .call_type(Call::new().callee(record.address).gas_limit(0))
+                .exec_input(ExecutionInput::new(Selector::new(selector)))
+                .returns::<Result<(), GameError>>()
+                .try_invoke()
+                .map_err(|_| RouterError::FailedToForceGame)?
+                .map_err(|_| RouterError::FailedToForceGame)?
+                .map_err(|_| RouterError::FailedToForceGame)
+        }
+
+        /// Checks the configs against the rules common to every game, without instantiating
+        /// a game.
+        ///
+        /// The rules specific to the game type are checked by the game's constructor.
+        #[ink(message)]
+        pub fn validate_configs(
+            &self,
+            which: GameTypeId,
+            configs: GameConfigs,
+        ) -> Result<(), RouterError> {
+            self.get_game_type(which)?;
+            configs.validate().map_err(RouterError::InvalidGameConfig)
+        }
+
+        /// Get a game instantiated by the router.
+        #[ink(message)]
+        pub fn get_game(&self, id: u32) -> Option<GameRecord> {
+            self.games.get(id)
+        }
+
+        /// Get up to `limit` games, skipping the first `offset` games.
+        #[ink(message)]
+        pub fn list_games(&self, offset: u32, limit: u32) -> Vec<GameRecord> {
+            let from = offset.saturating_add(1);
+            let to = from
+                .saturating_add(limit)
+                .min(self.games_count.saturating_add(1));
+
+            (from..to).filter_map(|id| self.games.get(id)).collect()
+        }
+
+        /// Get up to `limit` of the games instantiated by `account`, skipping the first `offset`.
+        #[ink(message)]
+        pub fn games_by_creator(
+            &self,
+            account: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<GameRecord> {
+            let count = self.creator_games_count.get(account).unwrap_or(0);
+            page(count, offset, limit)
+                .filter_map(|position| self.creator_games.get((account, position)))
+                .filter_map(|id| self.games.get(id))
+                .collect()
+        }
+
+        /// Get the number of games in `status`.
+        #[ink(message)]
+        pub fn count_games_by_status(&self, status: GameStatus) -> u32 {
+            self.status_games_count.get(status).unwrap_or(0)
+        }
+
+        /// Get up to `limit` of the games in `status`, skipping the first `offset`, as reported
+        /// by the games through `RouterCallbacks`.
+        ///
+        /// The games are not in any particular order, the order changes as games change status.
+        #[ink(message)]
+        pub fn list_games_by_status(
+            &self,
+            status: GameStatus,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<GameRecord> {
+            page(self.count_games_by_status(status), offset, limit)
+                .filter_map(|position| self.status_games.get((status, position)))
+                .filter_map(|id| self.games.get(id))
+                .collect()
+        }
+
+        /// Get up to `limit` of the games `account` played in, skipping the first `offset`, i.e.
+        /// the games it was a player of when they started.
+        #[ink(message)]
+        pub fn games_of_player(
+            &self,
+            account: AccountId,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<GameRecord> {
+            let count = self.player_games_count.get(account).unwrap_or(0);
+            page(count, offset, limit)
+                .filter_map(|position| self.player_games.get((account, position)))
+                .filter_map(|id| self.games.get(id))
+                .collect()
+        }
+
+        /// Get the default configs of a game type, used when no configs are given to `new_game`.
+        #[ink(message)]
+        pub fn get_default_configs(&self, which: GameTypeId) -> Result<GameConfigs, RouterError> {
+            Ok(self.get_game_type(which)?.default_configs)
+        }
+
+        /// Get a named config template.
+        #[ink(message)]
+        pub fn get_template(&self, name: String) -> Option<(GameTypeId, GameConfigs)> {
+            self.templates.get(name)
+        }
+
+        /// Stores (or replaces) a named config template for a game type.
+        ///
+        /// The configs are validated against the rules common to every game.
+        #[ink(message)]
+        #[modifiers(only_role(TEMPLATE_MANAGER))]
+        pub fn set_template(
+            &mut self,
+            name: String,
+            which: GameTypeId,
+            configs: GameConfigs,
+        ) -> Result<(), RouterError> {
+            self.validate_configs(which, configs.clone())?;
+            self.templates.insert(name, &(which, configs));
+            Ok(())
+        }
+
+        /// A methods that adds a game and instantiates its contract.
+        ///
+        /// The game is created with `configs` or, if none are given, with the default configs
+        /// of the game type. It runs the pinned `version` of the game type's code or, if none
+        /// is given, the latest version which isn't deprecated. Returns the address of the new game.
+        ///
+        /// The caller is the creator of the game (see `GameConfigs::creator`), the value paid is
+        /// their endowment of the game.
+        #[ink(message, payable)]
+        pub fn new_game(
+            &mut self,
+            which: GameTypeId,
+            configs: Option<GameConfigs>,
+            version: Option<u32>,
+        ) -> Result<AccountId, RouterError> {
+            let configs = match configs {
+                Some(configs) => configs,
+                None => self.get_default_configs(which)?,
+            };
+            self.instantiate_game(
+                which,
+                configs,
+                version,
+                self.env().caller(),
+                self.env().transferred_value(),
+            )
+        }
+
+        /// Queues the caller for a game of type `which` at the given `stake`, which must be paid.
+        ///
+        /// Once `min_players` of the game type's default configs are waiting at the same stake,
+        /// a game is instantiated with those configs, played for the stake (it's the minimum and
+        /// maximum round contribution), and the players are added to it through
+        /// `Admin::add_player_to_game`, along with their stake held as their deposit. Returns the
+        /// address of the game if a match was created.
+        #[ink(message, payable)]
+        pub fn enqueue(
+            &mut self,
+            which: GameTypeId,
+            stake: Balance,
+        ) -> Result<Option<AccountId>, RouterError> {
+            if self.env().transferred_value() != stake {
+                return Err(RouterError::StakeMismatch);
+            }
+            let game_type = self.get_game_type(which)?;
+
+            let player = self.env().caller();
+            let mut queue = self.queues.get((which, stake)).unwrap_or_default();
+            if queue.contains(&player) {
+                return Err(RouterError::AlreadyQueued);
+            }
+            queue.push(player);
+            self.queued_stakes += stake;
+
+            let min_players = usize::from(game_type.default_configs.min_players.max(1));
+            if queue.len() < min_players {
+                self.queues.insert((which, stake), &queue);
+                return Ok(None);
+            }
+
+            // the first players in the queue are matched
+            let players: Vec<AccountId> = queue.drain(..min_players).collect();
+            self.queues.insert((which, stake), &queue);
+            self.queued_stakes -= stake * players.len() as Balance;
+
+            // the players are matched to play for the stake
+            let mut configs = game_type.default_configs;
+            configs.min_round_contribution = Some(stake);
+            configs.max_round_contribution = Some(stake);
+            // nobody in particular creates the match, the router does
+            let address =
+                self.instantiate_game(which, configs, None, self.env().account_id(), 0)?;
+            for player in players.iter() {
+                build_call::<Environment>()
+                    .call_type(Call::new().callee(address).gas_limit(0))
+                    .transferred_value(stake)
+                    .exec_input(
+                        ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                            "Admin::add_player_to_game"
+                        )))
+                        .push_arg(player),
+                    )
+                    .returns::<Result<u8, GameError>>()
+                    .try_invoke()
+                    .map_err(|_| RouterError::FailedToAddPlayer)?
+                    .map_err(|_| RouterError::FailedToAddPlayer)?
+                    .map_err(|_| RouterError::FailedToAddPlayer)?;
+            }
+
+            self.env().emit_event(MatchCreated {
+                game_type: which,
+                game_address: address,
+                stake,
+                players,
+            });
+
+            Ok(Some(address))
+        }
+
+        /// Removes the caller from the queue of game type `which` at `stake`, and refunds the stake.
+        #[ink(message)]
+        pub fn dequeue(&mut self, which: GameTypeId, stake: Balance) -> Result<(), RouterError> {
+            let player = self.env().caller();
+            let mut queue = self.queues.get((which, stake)).unwrap_or_default();
+            let position = queue
+                .iter()
+                .position(|queued| *queued == player)
+                .ok_or(RouterError::NotQueued)?;
+
+            queue.remove(position);
+            self.queues.insert((which, stake), &queue);
+            self.queued_stakes -= stake;
+
+            self.env()
+                .transfer(player, stake)
+                .map_err(|_| RouterError::FailedToRefundStake)
+        }
+
+        /// Get the players waiting for a game of type `which` at `stake`.
+        #[ink(message)]
+        pub fn get_queue(&self, which: GameTypeId, stake: Balance) -> Vec<AccountId> {
+            self.queues.get((which, stake)).unwrap_or_default()
+        }
+
+        /// Adds a game using the configs of a named template.
+        ///
+        /// Returns the address of the new game.
+        #[ink(message, payable)]
+        pub fn new_game_from_template(&mut self, name: String) -> Result<AccountId, RouterError> {
+            let (which, configs) = self
+                .get_template(name)
+                .ok_or(RouterError::TemplateNotFound)?;
+            self.instantiate_game(
+                which,
+                configs,
+                None,
+                self.env().caller(),
+                self.env().transferred_value(),
+            )
+        }
+
+        /// Get the record of the game calling the router, fails if the router didn't
+        /// instantiate it.
+        fn caller_game(&self) -> Result<GameRecord, GameError> {
+            self.game_ids
+                .get(self.env().caller())
+                .and_then(|id| self.games.get(id))
+                .ok_or(GameError::GameNotRegistered)
+        }
+
+        /// Moves a game to the index of its new status and saves its record.
+        fn update_status(&mut self, record: &mut GameRecord, status: GameStatus) {
+            if record.status != status {
+                self.remove_status_game(record.status, record.id);
+                self.add_status_game(status, record.id);
+            }
+
+            record.status = status;
+            self.games.insert(record.id, record);
+        }
+
+        /// Appends a game to the index of `status`.
+        fn add_status_game(&mut self, status: GameStatus, id: u32) {
+            let count = self.count_games_by_status(status);
+            self.status_games.insert((status, count), &id);
+            self.status_games_count.insert(status, &(count + 1));
+            self.status_positions.insert(id, &count);
+        }
+
+        /// Removes a game from the index of `status`, the last game of the index takes its place.
+        fn remove_status_game(&mut self, status: GameStatus, id: u32) {
+            let position = match self.status_positions.get(id) {
+                Some(position) => position,
+                None => return,
+            };
+            let last = self.count_games_by_status(status).saturating_sub(1);
+            if position != last {
+                if let Some(last_id) = self.status_games.get((status, last)) {
+                    self.status_games.insert((status, position), &last_id);
+                    self.status_positions.insert(last_id, &position);
                 }
             }
+            self.status_games.remove((status, last));
+            self.status_games_count.insert(status, &last);
+            self.status_positions.remove(id);
+        }
+
+        /// Validates the configs, instantiates the game's contract and registers it.
+        ///
+        /// The `endowment` is paid to the game, as the endowment of its `creator`.
+        fn instantiate_game(
+            &mut self,
+            which: GameTypeId,
+            mut configs: GameConfigs,
+            version: Option<u32>,
+            creator: AccountId,
+            endowment: Balance,
+        ) -> Result<AccountId, RouterError> {
+            let game_type = self.get_game_type(which)?;
+            // have the game report its lifecycle and pay its fees back to the router
+            configs.router = Some(self.env().account_id());
+            configs.protocol_fee_bps = game_type.fee_bps;
+            // the creator administers the game along with the router
+            configs.creator = Some(creator);
+
+            // reject invalid configs before paying for the instantiation
+            self.validate_configs(which, configs.clone())?;
+
+            let game_version = self.resolve_version(which, version)?;
+            let id = self.games_count + 1;
+
+            // the instantiation, the call to the constructor and the constructor itself can fail
+            let address = build_create::<GameRef>()
+                .code_hash(game_version.code_hash)
+                .gas_limit(0)
+                .endowment(endowment)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(game_type.constructor_selector))
+                        .push_arg(configs),
+                )
+                .salt_bytes(id.to_le_bytes())
+                .returns::<Result<GameRef, GameError>>()
+                .try_instantiate()
+                .map_err(|_| RouterError::FailedToInstantiateGame)?
+                .map_err(|_| RouterError::FailedToInstantiateGame)?
+                .map_err(RouterError::InvalidGameConfig)?
+                .to_account_id();
+
+            self.register_game(which, &game_version, address, creator);
+
+            Ok(address)
+        }
+
+        /// Registers a game instantiated by the router, created by `creator`.
+        ///
+        /// Returns the id of the game.
+        fn register_game(
+            &mut self,
+            which: GameTypeId,
+            game_version: &GameVersion,
+            address: AccountId,
+            creator: AccountId,
+        ) -> u32 {
+            let id = self.games_count + 1;
+            self.games_count = id;
+            self.games.insert(
+                id,
+                &GameRecord {
+                    id,
+                    game_type: which,
+                    version: game_version.version,
+                    address,
+                    creator,
+                    created_at: self.env().block_number(),
+                    code_hash: game_version.code_hash,
+                    status: GameStatus::Ready,
+                    players: Vec::new(),
+                    rounds_completed: 0,
+                    fees_collected: 0,
+                },
+            );
+            self.game_ids.insert(address, &id);
+            self.add_status_game(GameStatus::Ready, id);
+
+            let count = self.creator_games_count.get(creator).unwrap_or(0);
+            self.creator_games.insert((creator, count), &id);
+            self.creator_games_count.insert(creator, &(count + 1));
+
+            id
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use game_theory::logics::traits::types::ConfigError;
+
+        fn get_accounts() -> ink::env::test::DefaultAccounts<Environment> {
+            ink::env::test::default_accounts::<Environment>()
+        }
+
+        fn set_caller(account: AccountId) {
+            ink::env::test::set_caller::<Environment>(account);
+        }
+
+        /// Registers a game at `address`, as if the router had instantiated it.
+        fn register_game(router: &mut Router, address: AccountId) -> u32 {
+            let version = GameVersion {
+                version: 1,
+                code_hash: Hash::from([1; 32]),
+                set_at: 0,
+                deprecated: false,
+            };
+            let creator = ink::env::caller::<Environment>();
+            router.register_game(1, &version, address, creator)
+        }
+
+        fn ids(games: Vec<GameRecord>) -> Vec<u32> {
+            games.into_iter().map(|game| game.id).collect()
+        }
+
+        /// Registers the game type `which` with the given default configs and no fee.
+        fn register_game_type(router: &mut Router, which: GameTypeId, configs: GameConfigs) {
+            let game_type = GameType {
+                name: String::from("Rock Paper Scissors"),
+                constructor_selector: ink::selector_bytes!("new"),
+                default_configs: configs,
+                fee_bps: 0,
+            };
+            assert_eq!(router.register_game_type(which, game_type), Ok(()));
+        }
+
+        fn invalid_configs() -> GameConfigs {
+            let mut configs = rock_paper_scissors::default_configs();
+            configs.min_players = configs.max_players + 1;
+            configs
+        }
+
+        fn missing_role() -> RouterError {
+            RouterError::AccessControlError(AccessControlError::MissingRole)
+        }
+
+        /// The owner holds every role of a new router.
+        #[ink::test]
+        fn new_grants_every_role_to_the_owner() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let router = Router::new();
+
+            assert_eq!(router.get_owner(), Some(accounts.alice));
+            for role in [
+                DEFAULT_ADMIN_ROLE,
+                GAME_MANAGER,
+                TEMPLATE_MANAGER,
+                FEE_COLLECTOR,
+            ] {
+                assert!(router.has_role(role, accounts.alice));
+                assert!(!router.has_role(role, accounts.bob));
+            }
+        }
+
+        /// The ownership is the admin role, transferring it moves the role.
+        #[ink::test]
+        fn ownership_follows_the_admin_role() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let mut router = Router::new();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                router.transfer_ownership(accounts.bob),
+                Err(RouterError::CallerIsNotOwner)
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                router.transfer_ownership(ZERO_ADDRESS.into()),
+                Err(RouterError::InvalidNewOwner)
+            );
+            assert_eq!(router.transfer_ownership(accounts.bob), Ok(()));
+            assert_eq!(router.get_owner(), Some(accounts.bob));
+            assert!(!router.has_role(DEFAULT_ADMIN_ROLE, accounts.alice));
+            // the other roles are kept until the new owner revokes them
+            assert!(router.has_role(GAME_MANAGER, accounts.alice));
+
+            set_caller(accounts.bob);
+            assert_eq!(router.renounce_ownership(), Ok(()));
+            assert_eq!(router.get_owner(), None);
+            assert_eq!(
+                router.transfer_ownership(accounts.bob),
+                Err(RouterError::OwnershipRenounced)
+            );
+        }
+
+        /// Games report their lifecycle, which indexes them by status and by player.
+        #[ink::test]
+        fn callbacks_update_the_game_records() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let mut router = Router::new();
+            for game in [accounts.django, accounts.eve, accounts.frank] {
+                register_game(&mut router, game);
+            }
+            assert_eq!(router.count_games_by_status(GameStatus::Ready), 3);
+
+            // only the games of the router can call back
+            set_caller(accounts.bob);
+            assert_eq!(
+                router.on_game_ended(GameStatus::Ended),
+                Err(GameError::GameNotRegistered)
+            );
+
+            set_caller(accounts.django);
+            assert_eq!(
+                router.on_game_started(vec![accounts.alice, accounts.bob]),
+                Ok(())
+            );
+            assert_eq!(router.on_round_completed(1, 25), Ok(()));
+            set_caller(accounts.eve);
+            assert_eq!(router.on_game_ended(GameStatus::Cancelled), Ok(()));
+
+            assert!(router.is_registered_game(accounts.django));
+            assert!(!router.is_registered_game(accounts.bob));
+
+            let game = router.get_game(1).unwrap();
+            assert_eq!(game.status, GameStatus::OnGoing);
+            assert_eq!(game.players, vec![accounts.alice, accounts.bob]);
+            assert_eq!(game.rounds_completed, 1);
+            assert_eq!(game.fees_collected, 25);
+            assert_eq!(router.games_of_player(accounts.bob, 0, 10), vec![game]);
+
+            assert_eq!(
+                ids(router.list_games_by_status(GameStatus::Ready, 0, 10)),
+                vec![3]
+            );
+            assert_eq!(
+                ids(router.list_games_by_status(GameStatus::OnGoing, 0, 10)),
+                vec![1]
+            );
+            assert_eq!(
+                ids(router.list_games_by_status(GameStatus::Cancelled, 0, 10)),
+                vec![2]
+            );
+        }
+
+        /// Only the fees reported by the games can be withdrawn, and only by a fee collector.
+        #[ink::test]
+        fn only_reported_fees_can_be_withdrawn() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let mut router = Router::new();
+            register_game(&mut router, accounts.django);
+            // the router also holds the queued stakes and whatever it is sent
+            ink::env::test::set_account_balance::<Environment>(
+                ink::env::test::callee::<Environment>(),
+                1_000_000,
+            );
+            assert_eq!(router.get_accrued_fees(), 0);
+            assert_eq!(
+                router.withdraw_fees(accounts.alice),
+                Err(RouterError::NoFeesToWithdraw)
+            );
+
+            set_caller(accounts.django);
+            assert_eq!(router.on_round_completed(1, 25), Ok(()));
+            // more fees collected from the same round later on
+            assert_eq!(router.on_round_completed(1, 15), Ok(()));
+            assert_eq!(router.get_accrued_fees(), 40);
+            let game = router.get_game(1).unwrap();
+            assert_eq!(game.rounds_completed, 1);
+            assert_eq!(game.fees_collected, 40);
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                router.withdraw_fees(accounts.bob),
+                Err(RouterError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(router.withdraw_fees(accounts.charlie), Ok(40));
+            assert_eq!(router.get_accrued_fees(), 0);
+            assert_eq!(
+                router.withdraw_fees(accounts.charlie),
+                Err(RouterError::NoFeesToWithdraw)
+            );
+        }
+
+        /// Only what the router holds besides the queued stakes and the accrued fees can be
+        /// withdrawn as its surplus.
+        #[ink::test]
+        fn only_the_surplus_can_be_withdrawn() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let mut router = Router::new();
+            register_game(&mut router, accounts.django);
+            register_game_type(&mut router, 1, rock_paper_scissors::default_configs());
+
+            // bob waits for a match
+            set_caller(accounts.bob);
+            ink::env::test::set_value_transferred::<Environment>(100);
+            assert_eq!(router.enqueue(1, 100), Ok(None));
+            ink::env::test::set_value_transferred::<Environment>(0);
+            set_caller(accounts.django);
+            assert_eq!(router.on_round_completed(1, 40), Ok(()));
+            ink::env::test::set_account_balance::<Environment>(
+                ink::env::test::callee::<Environment>(),
+                ink::env::minimum_balance::<Environment>() + 100 + 40 + 25,
+            );
+            assert_eq!(router.get_surplus(), 25);
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                router.withdraw_surplus(accounts.bob),
+                Err(RouterError::AccessControlError(
+                    AccessControlError::MissingRole
+                ))
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(router.withdraw_surplus(accounts.charlie), Ok(25));
+            assert_eq!(router.get_surplus(), 0);
+            assert_eq!(router.get_accrued_fees(), 40);
+            assert_eq!(
+                router.withdraw_surplus(accounts.charlie),
+                Err(RouterError::NoSurplusToWithdraw)
+            );
+        }
+
+        /// Game types are registered by the game managers, with valid default configs and fee.
+        #[ink::test]
+        fn game_managers_register_game_types() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let mut router = Router::new();
+            let mut game_type = GameType {
+                name: String::from("Rock Paper Scissors"),
+                constructor_selector: ink::selector_bytes!("new"),
+                default_configs: rock_paper_scissors::default_configs(),
+                fee_bps: MAX_FEE_BPS + 1,
+            };
+            assert_eq!(
+                router.register_game_type(1, game_type.clone()),
+                Err(RouterError::InvalidFee)
+            );
+            game_type.fee_bps = 100;
+            game_type.default_configs = invalid_configs();
+            assert_eq!(
+                router.register_game_type(1, game_type.clone()),
+                Err(RouterError::InvalidGameConfig(GameError::InvalidConfig(
+                    ConfigError::MinPlayersAboveMax
+                )))
+            );
+            game_type.default_configs = rock_paper_scissors::default_configs();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                router.register_game_type(1, game_type.clone()),
+                Err(missing_role())
+            );
+            assert_eq!(router.set_game_fee(1, 50), Err(missing_role()));
+
+            set_caller(accounts.alice);
+            assert_eq!(router.register_game_type(1, game_type.clone()), Ok(()));
+            assert_eq!(router.get_game_type(1), Ok(game_type));
+            assert_eq!(
+                router.get_default_configs(1),
+                Ok(rock_paper_scissors::default_configs())
+            );
+            assert_eq!(
+                router.set_game_fee(1, MAX_FEE_BPS + 1),
+                Err(RouterError::InvalidFee)
+            );
+            assert_eq!(router.set_game_fee(1, 50), Ok(()));
+            assert_eq!(router.get_game_type(1).unwrap().fee_bps, 50);
+            assert_eq!(
+                router.set_game_fee(2, 50),
+                Err(RouterError::GameTypeNotFound)
+            );
+
+            assert_eq!(router.unregister_game_type(1), Ok(()));
+            assert_eq!(router.get_game_type(1), Err(RouterError::GameTypeNotFound));
+            assert_eq!(
+                router.unregister_game_type(1),
+                Err(RouterError::GameTypeNotFound)
+            );
+        }
+
+        /// New games run the latest version of the code of their type which isn't deprecated.
+        #[ink::test]
+        fn versions_resolve_to_the_latest_not_deprecated() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let mut router = Router::new();
+            let (first, second) = (Hash::from([1; 32]), Hash::from([2; 32]));
+            assert_eq!(
+                router.set_game_hash(1, first),
+                Err(RouterError::GameTypeNotFound)
+            );
+
+            register_game_type(&mut router, 1, rock_paper_scissors::default_configs());
+            assert_eq!(router.get_game_hash(1), Err(RouterError::VersionNotFound));
+            assert_eq!(router.set_game_hash(1, first), Ok(1));
+            assert_eq!(router.set_game_hash(1, second), Ok(2));
+            assert_eq!(router.get_game_hash(1), Ok(second));
+            assert_eq!(
+                router
+                    .get_game_versions(1)
+                    .into_iter()
+                    .map(|version| version.code_hash)
+                    .collect::<Vec<_>>(),
+                vec![first, second]
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                router.set_version_deprecated(1, 2, true),
+                Err(missing_role())
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(router.set_version_deprecated(1, 2, true), Ok(()));
+            assert!(router.get_game_version(1, 2).unwrap().deprecated);
+            assert_eq!(router.get_game_hash(1), Ok(first));
+            assert_eq!(
+                router.resolve_version(1, Some(2)),
+                Err(RouterError::VersionDeprecated)
+            );
+            assert_eq!(
+                router.set_version_deprecated(1, 3, true),
+                Err(RouterError::VersionNotFound)
+            );
+
+            assert_eq!(router.set_version_deprecated(1, 1, true), Ok(()));
+            assert_eq!(router.get_game_hash(1), Err(RouterError::VersionNotFound));
+            assert_eq!(router.set_version_deprecated(1, 2, false), Ok(()));
+            assert_eq!(router.get_game_hash(1), Ok(second));
+        }
+
+        /// New games are only instantiated for a known game type, with valid configs and an
+        /// available version.
+        #[ink::test]
+        fn new_game_rejects_invalid_requests() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let mut router = Router::new();
+            assert_eq!(
+                router.new_game(1, None, None),
+                Err(RouterError::GameTypeNotFound)
+            );
+
+            register_game_type(&mut router, 1, rock_paper_scissors::default_configs());
+            assert_eq!(
+                router.new_game(1, Some(invalid_configs()), None),
+                Err(RouterError::InvalidGameConfig(GameError::InvalidConfig(
+                    ConfigError::MinPlayersAboveMax
+                )))
+            );
+            assert_eq!(
+                router.new_game(1, None, None),
+                Err(RouterError::VersionNotFound)
+            );
+
+            assert_eq!(router.set_game_hash(1, Hash::from([1; 32])), Ok(1));
+            assert_eq!(router.set_version_deprecated(1, 1, true), Ok(()));
+            assert_eq!(
+                router.new_game(1, None, Some(1)),
+                Err(RouterError::VersionDeprecated)
+            );
+            assert_eq!(
+                router.new_game(1, None, Some(2)),
+                Err(RouterError::VersionNotFound)
+            );
+            assert_eq!(
+                router.new_game_from_template(String::from("blitz")),
+                Err(RouterError::TemplateNotFound)
+            );
+            assert_eq!(router.list_games(0, 10), vec![]);
+        }
+
+        /// Templates are managed by the template managers, with valid configs.
+        #[ink::test]
+        fn template_managers_set_templates() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let mut router = Router::new();
+            let name = String::from("blitz");
+            let mut configs = rock_paper_scissors::default_configs();
+            configs.round_timeout = Some(10);
+            assert_eq!(
+                router.set_template(name.clone(), 1, configs.clone()),
+                Err(RouterError::GameTypeNotFound)
+            );
+
+            register_game_type(&mut router, 1, rock_paper_scissors::default_configs());
+            assert_eq!(
+                router.set_template(name.clone(), 1, invalid_configs()),
+                Err(RouterError::InvalidGameConfig(GameError::InvalidConfig(
+                    ConfigError::MinPlayersAboveMax
+                )))
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                router.set_template(name.clone(), 1, configs.clone()),
+                Err(missing_role())
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                router.set_template(name.clone(), 1, configs.clone()),
+                Ok(())
+            );
+            assert_eq!(router.get_template(name), Some((1, configs)));
+            assert_eq!(router.get_template(String::from("rapid")), None);
+        }
+
+        /// Only the game managers can upgrade the games of the router, to an available version.
+        #[ink::test]
+        fn upgrade_game_rejects_invalid_requests() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let mut router = Router::new();
+            assert_eq!(router.upgrade_game(1, None), Err(RouterError::GameNotFound));
+
+            register_game_type(&mut router, 1, rock_paper_scissors::default_configs());
+            register_game(&mut router, accounts.django);
+            assert_eq!(
+                router.upgrade_game(1, None),
+                Err(RouterError::VersionNotFound)
+            );
+            assert_eq!(router.set_game_hash(1, Hash::from([2; 32])), Ok(1));
+            assert_eq!(router.set_version_deprecated(1, 1, true), Ok(()));
+            assert_eq!(
+                router.upgrade_game(1, Some(1)),
+                Err(RouterError::VersionDeprecated)
+            );
+
+            set_caller(accounts.bob);
+            assert_eq!(router.upgrade_game(1, None), Err(missing_role()));
+            assert_eq!(router.get_game(1).unwrap().version, 1);
+        }
+
+        /// Players wait in the queue of a game type and stake until enough of them are queued.
+        #[ink::test]
+        fn players_wait_in_the_queue_until_matched() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let mut router = Router::new();
+            let mut configs = rock_paper_scissors::default_configs();
+            configs.min_players = 3;
+            configs.max_players = 3;
+            register_game_type(&mut router, 1, configs);
+            ink::env::test::set_account_balance::<Environment>(
+                ink::env::test::callee::<Environment>(),
+                1_000_000,
+            );
+
+            set_caller(accounts.bob);
+            ink::env::test::set_value_transferred::<Environment>(99);
+            assert_eq!(router.enqueue(1, 100), Err(RouterError::StakeMismatch));
+            ink::env::test::set_value_transferred::<Environment>(100);
+            assert_eq!(router.enqueue(2, 100), Err(RouterError::GameTypeNotFound));
+            assert_eq!(router.enqueue(1, 100), Ok(None));
+            assert_eq!(router.enqueue(1, 100), Err(RouterError::AlreadyQueued));
+            set_caller(accounts.charlie);
+            assert_eq!(router.enqueue(1, 100), Ok(None));
+            assert_eq!(
+                router.get_queue(1, 100),
+                vec![accounts.bob, accounts.charlie]
+            );
+            // the queues are split by stake
+            assert_eq!(router.get_queue(1, 50), vec![]);
+            // the queued stakes aren't fees
+            assert_eq!(router.get_accrued_fees(), 0);
+
+            set_caller(accounts.django);
+            assert_eq!(router.dequeue(1, 100), Err(RouterError::NotQueued));
+            set_caller(accounts.bob);
+            assert_eq!(router.dequeue(1, 100), Ok(()));
+            assert_eq!(router.get_queue(1, 100), vec![accounts.charlie]);
+            assert_eq!(router.dequeue(1, 100), Err(RouterError::NotQueued));
+        }
+
+        /// The lists of games are read a page at a time.
+        #[ink::test]
+        fn game_lists_are_paginated() {
+            let accounts = get_accounts();
+            set_caller(accounts.alice);
+            let mut router = Router::new();
+            for game in [accounts.django, accounts.eve, accounts.frank] {
+                register_game(&mut router, game);
+            }
+
+            assert_eq!(
+                ids(router.games_by_creator(accounts.alice, 0, 2)),
+                vec![1, 2]
+            );
+            assert_eq!(ids(router.games_by_creator(accounts.alice, 1, 1)), vec![2]);
+            assert_eq!(ids(router.games_by_creator(accounts.alice, 2, 10)), vec![3]);
+            assert_eq!(router.games_by_creator(accounts.alice, 3, 10), vec![]);
+            assert_eq!(router.games_by_creator(accounts.bob, 0, 10), vec![]);
+            assert_eq!(
+                ids(router.list_games_by_status(GameStatus::Ready, 1, 10)),
+                vec![2, 3]
+            );
+            assert_eq!(ids(router.list_games(1, 1)), vec![2]);
+        }
+    }
+
+    /// On-chain (E2E) tests, the router instantiates and calls the games it manages.
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use game_theory::logics::traits::{admin::Admin, basic::Basic, escrow::Escrow};
+        use ink_e2e::build_message;
+        use rock_paper_scissors::RockPaperScissorsRef;
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+        type Client = ink_e2e::Client<ink_e2e::PolkadotConfig, ink::env::DefaultEnvironment>;
+
+        /// Instantiates a router with the rock paper scissors game registered as game type 1,
+        /// returns the address of the router and the code hash of the game.
+        async fn setup_router(client: &mut Client) -> (AccountId, Hash) {
+            let router = client
+                .instantiate("router", &ink_e2e::alice(), RouterRef::new(), 0, None)
+                .await
+                .expect("instantiation failed")
+                .account_id;
+            let code_hash = client
+                .upload("rock_paper_scissors", &ink_e2e::alice(), None)
+                .await
+                .expect("upload failed")
+                .code_hash;
+
+            let register = build_message::<RouterRef>(router.clone()).call(|router| {
+                router.register_game_type(
+                    1,
+                    GameType {
+                        name: String::from("Rock Paper Scissors"),
+                        constructor_selector: ink::selector_bytes!("new"),
+                        default_configs: rock_paper_scissors::default_configs(),
+                        fee_bps: 0,
+                    },
+                )
+            });
+            client
+                .call(&ink_e2e::alice(), register, 0, None)
+                .await
+                .expect("register_game_type failed");
+            let set_hash = build_message::<RouterRef>(router.clone())
+                .call(|router| router.set_game_hash(1, code_hash));
+            client
+                .call(&ink_e2e::alice(), set_hash, 0, None)
+                .await
+                .expect("set_game_hash failed");
+
+            (router, code_hash)
+        }
+
+        /// Creates a game of type 1 with the default configs as bob, returns its address.
+        async fn new_game(client: &mut Client, router: AccountId) -> AccountId {
+            let new_game = build_message::<RouterRef>(router.clone())
+                .call(|router| router.new_game(1, None, None));
+            client
+                .call(&ink_e2e::bob(), new_game, 0, None)
+                .await
+                .expect("new_game failed")
+                .return_value()
+                .expect("the game wasn't instantiated")
+        }
+
+        /// New games are instantiated with the configs of their type, reporting to the router,
+        /// and registered with their address.
+        #[ink_e2e::test(additional_contracts = "../rock_paper_scissors/Cargo.toml")]
+        async fn new_game_registers_the_instantiated_game(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let (router, code_hash) = setup_router(&mut client).await;
+            let game = new_game(&mut client, router).await;
+
+            let get_game =
+                build_message::<RouterRef>(router.clone()).call(|router| router.get_game(1));
+            let record = client
+                .call_dry_run(&ink_e2e::alice(), &get_game, 0, None)
+                .await
+                .return_value()
+                .expect("the game wasn't registered");
+            assert_eq!(record.address, game);
+            assert_eq!(record.code_hash, code_hash);
+            assert_eq!(record.version, 1);
+            assert_eq!(record.status, GameStatus::Ready);
+            assert_eq!(
+                record.creator,
+                ink_e2e::account_id(ink_e2e::AccountKeyring::Bob)
+            );
+
+            // the game reports to the router
+            let get_configs =
+                build_message::<RockPaperScissorsRef>(game.clone()).call(|game| game.get_configs());
+            let configs = client
+                .call_dry_run(&ink_e2e::alice(), &get_configs, 0, None)
+                .await
+                .return_value();
+            assert_eq!(configs.router, Some(router));
+
+            Ok(())
+        }
+
+        /// The account calling `new_game` administers the game, and the value it paid is
+        /// its endowment of the game.
+        #[ink_e2e::test(additional_contracts = "../rock_paper_scissors/Cargo.toml")]
+        async fn new_game_is_administered_by_its_creator(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let (router, _) = setup_router(&mut client).await;
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            let endowment = 1_000_000;
+            let new_game = build_message::<RouterRef>(router.clone())
+                .call(|router| router.new_game(1, None, None));
+            let game = client
+                .call(&ink_e2e::bob(), new_game, endowment, None)
+                .await
+                .expect("new_game failed")
+                .return_value()
+                .expect("the game wasn't instantiated");
+
+            let get_configs =
+                build_message::<RockPaperScissorsRef>(game.clone()).call(|game| game.get_configs());
+            let configs = client
+                .call_dry_run(&ink_e2e::alice(), &get_configs, 0, None)
+                .await
+                .return_value();
+            assert_eq!(configs.creator, Some(bob));
+
+            // bob can end the game, and gets their endowment back
+            let force_end_game = build_message::<RockPaperScissorsRef>(game.clone())
+                .call(|game| game.force_end_game());
+            let result = client
+                .call(&ink_e2e::bob(), force_end_game, 0, None)
+                .await
+                .expect("force_end_game failed");
+            assert_eq!(result.return_value(), Ok(()));
+            let get_claimable = build_message::<RockPaperScissorsRef>(game.clone())
+                .call(|game| game.get_claimable(bob));
+            let claimable = client
+                .call_dry_run(&ink_e2e::alice(), &get_claimable, 0, None)
+                .await
+                .return_value();
+            assert_eq!(claimable, endowment);
+
+            Ok(())
+        }
+
+        /// The router migrates its running games to the latest version of their code.
+        #[ink_e2e::test(additional_contracts = "../rock_paper_scissors/Cargo.toml")]
+        async fn upgrade_game_migrates_the_game(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let (router, code_hash) = setup_router(&mut client).await;
+            new_game(&mut client, router).await;
+
+            // the same code is added as a second version
+            let set_hash = build_message::<RouterRef>(router.clone())
+                .call(|router| router.set_game_hash(1, code_hash));
+            client
+                .call(&ink_e2e::alice(), set_hash, 0, None)
+                .await
+                .expect("set_game_hash failed");
+            let upgrade = build_message::<RouterRef>(router.clone())
+                .call(|router| router.upgrade_game(1, None));
+            let result = client
+                .call(&ink_e2e::alice(), upgrade, 0, None)
+                .await
+                .expect("upgrade_game failed");
+            assert_eq!(result.return_value(), Ok(2));
+
+            let get_game =
+                build_message::<RouterRef>(router.clone()).call(|router| router.get_game(1));
+            let record = client
+                .call_dry_run(&ink_e2e::alice(), &get_game, 0, None)
+                .await
+                .return_value()
+                .unwrap();
+            assert_eq!(record.version, 2);
+
+            Ok(())
+        }
+
+        /// The players matched by the queue are added to a new game along with their stakes.
+        #[ink_e2e::test(additional_contracts = "../rock_paper_scissors/Cargo.toml")]
+        async fn enqueue_matches_players_and_forwards_their_stakes(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let (router, _) = setup_router(&mut client).await;
+
+            let stake = 1_000_000;
+            let enqueue =
+                build_message::<RouterRef>(router.clone()).call(|router| router.enqueue(1, stake));
+            let result = client
+                .call(&ink_e2e::bob(), enqueue.clone(), stake, None)
+                .await
+                .expect("enqueue failed");
+            assert_eq!(result.return_value(), Ok(None));
+            let result = client
+                .call(&ink_e2e::charlie(), enqueue, stake, None)
+                .await
+                .expect("enqueue failed");
+            let game = result.return_value().unwrap().expect("no match created");
+
+            // the game holds the stakes as the deposits of the players
+            let get_players =
+                build_message::<RockPaperScissorsRef>(game.clone()).call(|game| game.get_players());
+            let players = client
+                .call_dry_run(&ink_e2e::alice(), &get_players, 0, None)
+                .await
+                .return_value();
+            assert_eq!(
+                players,
+                vec![
+                    ink_e2e::account_id(ink_e2e::AccountKeyring::Bob),
+                    ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie),
+                ]
+            );
+            assert!(client.balance(game).await? >= 2 * stake);
+
+            // and the rounds are played for the stake
+            let get_configs =
+                build_message::<RockPaperScissorsRef>(game.clone()).call(|game| game.get_configs());
+            let configs = client
+                .call_dry_run(&ink_e2e::alice(), &get_configs, 0, None)
+                .await
+                .return_value();
+            assert_eq!(configs.min_round_contribution, Some(stake));
+            assert_eq!(configs.max_round_contribution, Some(stake));
+
+            let get_accrued_fees =
+                build_message::<RouterRef>(router.clone()).call(|router| router.get_accrued_fees());
+            let fees = client
+                .call_dry_run(&ink_e2e::alice(), &get_accrued_fees, 0, None)
+                .await
+                .return_value();
+            assert_eq!(fees, 0);
 
             Ok(())
         }
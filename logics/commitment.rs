@@ -0,0 +1,62 @@
+use crate::logics::traits::types::PointsAssignment;
+use ink::env::hash::{Blake2x256, HashOutput};
+use ink::prelude::vec::Vec;
+use openbrush::traits::{AccountId, Hash};
+use scale::Encode;
+
+/// Prefix of every commitment pre-image, keeps these hashes apart from any other Blake2 usage.
+pub const COMMITMENT_DOMAIN: &[u8] = b"game-theory/commitment";
+
+/// Prefix of every actions commitment pre-image, keeps them apart from the round commitments.
+pub const ACTIONS_DOMAIN: &[u8] = b"game-theory/actions";
+
+/// Computes the commitment to `payload` made by `player` in round `round_id` of the game
+/// deployed at `game`.
+///
+/// Binding the game, round and player into the hash means a commitment cannot be copied
+/// by another player, replayed in a later round or reused in another game instance.
+///
+/// The pre-image is `COMMITMENT_DOMAIN || game || round_id || player || payload.0 || payload.1`
+/// with the numbers SCALE (i.e. little-endian) encoded.
+pub fn hash_commitment(
+    game: &AccountId,
+    round_id: u8,
+    player: &AccountId,
+    payload: (u128, u128),
+) -> Hash {
+    let mut data: Vec<u8> = Vec::with_capacity(COMMITMENT_DOMAIN.len() + 32 + 1 + 32 + 16 + 16);
+    data.extend_from_slice(COMMITMENT_DOMAIN);
+    game.encode_to(&mut data);
+    round_id.encode_to(&mut data);
+    player.encode_to(&mut data);
+    payload.0.encode_to(&mut data);
+    payload.1.encode_to(&mut data);
+
+    let mut output = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Blake2x256>(&data, &mut output);
+    output.into()
+}
+
+/// Computes the commitment to the points `actions` assigned by `player` in the stage following
+/// round `round_id` of the game deployed at `game`, see `GameConfigs::post_round_actions`.
+///
+/// The pre-image is `ACTIONS_DOMAIN || game || round_id || player || actions || nonce` with
+/// everything SCALE encoded.
+pub fn hash_actions_commitment(
+    game: &AccountId,
+    round_id: u8,
+    player: &AccountId,
+    actions: &[PointsAssignment],
+    nonce: u128,
+) -> Hash {
+    let mut data: Vec<u8> = Vec::from(ACTIONS_DOMAIN);
+    game.encode_to(&mut data);
+    round_id.encode_to(&mut data);
+    player.encode_to(&mut data);
+    actions.encode_to(&mut data);
+    nonce.encode_to(&mut data);
+
+    let mut output = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Blake2x256>(&data, &mut output);
+    output.into()
+}
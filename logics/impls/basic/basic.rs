@@ -1,14 +1,110 @@
-
 pub use crate::{
-    impls::lifecycle::{ data, lifecycle },
-    traits::lifecycle::Lifecycle
+    logics::commitment::hash_commitment,
+    logics::impls::{
+        basic::data::Data,
+        lifecycle::{Hooks, LifecycleInternal},
+    },
+    logics::traits::{basic::*, types::*},
 };
-use openbrush::traits::AccountId;
-use openbrush::traits::Storage;
+use ink::prelude::vec::Vec;
+use openbrush::traits::{AccountId, DefaultEnv, Hash, Storage};
+
+impl<T: Storage<Data> + Hooks> Basic for T {
+    default fn get_configs(&self) -> GameConfigs {
+        self.data::<Data>().configs.clone()
+    }
+
+    default fn get_players(&self) -> Vec<AccountId> {
+        self.data::<Data>().players.clone()
+    }
+
+    default fn get_status(&self) -> GameStatus {
+        self.data::<Data>().status
+    }
+
+    default fn get_current_round(&self) -> Option<GameRound> {
+        self.data::<Data>().current_round.clone()
+    }
+
+    default fn get_round(&self, id: u32) -> Option<GameRound> {
+        self.data::<Data>().rounds.get(&id)
+    }
+
+    default fn get_rounds(&self, from: u32, limit: u32) -> Vec<GameRound> {
+        // round ids start at 1, so the last completed round has the id `rounds_count`
+        let to = from
+            .saturating_add(limit)
+            .min(self.data::<Data>().rounds_count.saturating_add(1));
+
+        (from..to).filter_map(|id| self.get_round(id)).collect()
+    }
+
+    default fn get_rounds_count(&self) -> u32 {
+        self.data::<Data>().rounds_count
+    }
+
+    default fn hash_commitment(
+        &self,
+        player: AccountId,
+        input: u128,
+        nonce: u128,
+    ) -> Result<Hash, GameError> {
+        let current_round = self
+            .data::<Data>()
+            .current_round
+            .as_ref()
+            .ok_or(GameError::NoCurrentRound)?;
+
+        Ok(hash_commitment(
+            &Self::env().account_id(),
+            current_round.id,
+            &player,
+            (input, nonce),
+        ))
+    }
+
+    default fn join(&mut self, player: AccountId) -> Result<u8, GameError> {
+        // ensure that joining is only done by caller
+        crate::ensure!(
+            Self::env().caller() == player,
+            GameError::CallerMustMatchNewPlayer
+        );
+        // ensure applicable fees are paid
+        if let Some(fees) = self.data::<Data>().configs.join_fee {
+            crate::ensure!(
+                Self::env().transferred_value() >= fees,
+                GameError::InsufficientJoiningFees
+            );
+        }
+
+        let players_count = self._add_player(player)?;
+
+        // keep track of the fees paid, to be refunded when the game ends
+        let value = Self::env().transferred_value();
+        if value > 0 {
+            self.data::<Data>().join_fees.push((player, value));
+        }
+
+        Ok(players_count)
+    }
+
+    default fn leave(&mut self) -> Result<u8, GameError> {
+        let player = Self::env().caller();
+        let data = self.data::<Data>();
+        // ensure the game hasn't started
+        crate::ensure!(
+            data.status == GameStatus::Ready,
+            GameError::InvalidGameState
+        );
+        // ensure the player is part of this game
+        crate::ensure!(data.players.contains(&player), GameError::PlayerNotInGame);
+
+        data.players.retain(|p| p != &player);
+        let players_count = data.players.len() as u8;
+        self._refund_join_fees(Some(player));
 
-// impl<T: Storage<data::Data>> Basic for T {
-//     default fn get_players(&self) -> Vec<AccountId> {
-//         self.data::<data::Data>().players.clone()
-//     }
-// }
+        self._emit_player_left_event(player);
 
+        Ok(players_count)
+    }
+}
@@ -1,29 +1,61 @@
-use ink::{
-    prelude::vec::Vec,
-    primitives::Hash,
-};
-use openbrush::{
-    storage::Mapping,
-    traits::{
-        AccountId,
-        ZERO_ADDRESS,
-    },
-};
+use crate::logics::traits::types::{GameConfigs, GameRound, GameStatus};
+use ink::prelude::vec::Vec;
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, ZERO_ADDRESS};
 
 pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
 
+/// The state shared by every game, used by the default `Basic` and `Lifecycle` implementations.
 #[derive(Debug)]
 #[openbrush::upgradeable_storage(STORAGE_KEY)]
 pub struct Data {
-    created_by: AccountId,
+    /// Account that created the instance of this game
+    pub created_by: AccountId,
     /// Stores the list of players for this game instance
-    players: Vec<AccountId>,
+    pub players: Vec<AccountId>,
     /// The status of the current game
-    status: GameStatus,
+    pub status: GameStatus,
     /// The current round of the game
-    current_round: Option<GameRound>,
+    pub current_round: Option<GameRound>,
     /// The id of the next round
-    next_round_id: u8,
+    pub next_round_id: u8,
+    /// Every completed round, by round id
+    pub rounds: Mapping<u32, GameRound>,
+    /// The number of completed rounds
+    pub rounds_count: u32,
     /// The configurations of the game
-    configs: GameConfigs
-}
\ No newline at end of file
+    pub configs: GameConfigs,
+    /// The balance each account can claim from the game (rewards, refunds, etc.)
+    pub claimable: Mapping<AccountId, Balance>,
+    /// The sum of all the claimable balances
+    pub total_claimable: Balance,
+    /// The join fees (and any other deposit) paid by each player when joining
+    pub join_fees: Vec<(AccountId, Balance)>,
+    /// The penalties incurred by each player during the game, already taken from their round
+    /// contributions
+    pub penalties: Mapping<AccountId, Balance>,
+    /// The endowments put into the game, along with the account which funded them
+    pub endowments: Vec<(AccountId, Balance)>,
+    pub _reserved: Option<()>,
+}
+
+impl Default for Data {
+    fn default() -> Self {
+        Self {
+            created_by: ZERO_ADDRESS.into(),
+            players: Vec::new(),
+            status: GameStatus::Ready,
+            current_round: None,
+            next_round_id: 1,
+            rounds: Default::default(),
+            rounds_count: 0,
+            configs: Default::default(),
+            claimable: Default::default(),
+            total_claimable: 0,
+            join_fees: Vec::new(),
+            penalties: Default::default(),
+            endowments: Vec::new(),
+            _reserved: None,
+        }
+    }
+}
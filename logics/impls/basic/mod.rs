@@ -1,2 +1,2 @@
+pub mod basic;
 pub mod data;
-pub mod basic;
\ No newline at end of file
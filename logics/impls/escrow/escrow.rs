@@ -0,0 +1,58 @@
+use crate::ensure;
+pub use crate::{
+    logics::impls::{basic::data::Data, lifecycle::Hooks},
+    logics::traits::{escrow::*, types::*},
+};
+use openbrush::traits::{AccountId, Balance, DefaultEnv, Storage};
+
+/// Helpers to manage the ledger of claimable balances.
+pub trait EscrowInternal {
+    /// Adds `amount` to the claimable balance of `account`.
+    fn _credit(&mut self, account: AccountId, amount: Balance);
+
+    /// Whether every credited balance has been claimed.
+    fn _is_settled(&self) -> bool;
+}
+
+impl<T: Storage<Data>> EscrowInternal for T {
+    default fn _credit(&mut self, account: AccountId, amount: Balance) {
+        if amount == 0 {
+            return;
+        }
+
+        let data = self.data::<Data>();
+        let balance = data.claimable.get(&account).unwrap_or(0);
+        data.claimable.insert(&account, &(balance + amount));
+        data.total_claimable += amount;
+    }
+
+    default fn _is_settled(&self) -> bool {
+        self.data::<Data>().total_claimable == 0
+    }
+}
+
+impl<T: Storage<Data> + Hooks> Escrow for T {
+    default fn get_claimable(&self, account: AccountId) -> Balance {
+        self.data::<Data>().claimable.get(&account).unwrap_or(0)
+    }
+
+    default fn claim(&mut self) -> Result<Balance, GameError> {
+        let caller = Self::env().caller();
+        let amount = self.get_claimable(caller);
+        // ensure there's something to claim
+        ensure!(amount > 0, GameError::NothingToClaim);
+
+        // update the ledger before transferring
+        let data = self.data::<Data>();
+        data.claimable.remove(&caller);
+        data.total_claimable -= amount;
+
+        Self::env()
+            .transfer(caller, amount)
+            .map_err(|_| GameError::ClaimFailed)?;
+
+        self._emit_claimed_event(caller, amount);
+
+        Ok(amount)
+    }
+}
@@ -0,0 +1,733 @@
+use crate::ensure;
+pub use crate::{
+    logics::commitment::hash_commitment,
+    logics::impls::{basic::data::Data, escrow::EscrowInternal},
+    logics::traits::{lifecycle::*, types::*},
+};
+use ink::env::call::{build_call, Call, ExecutionInput, Selector};
+use ink::env::DefaultEnvironment;
+use ink::prelude::vec::Vec;
+use openbrush::traits::{AccountId, Balance, DefaultEnv, Hash, Storage};
+
+/// Game specific behaviour plugged into the default `Basic` and `Lifecycle` implementations.
+///
+/// A game only has to provide `_settle_round`, every other hook has a default. Events are
+/// defined by each contract, so the `_emit_*` hooks are where a game emits its own events.
+pub trait Hooks: Storage<Data> {
+    /// Validates the configurations the game is created with.
+    ///
+    /// By default only the rules common to every game are checked, see `GameConfigs::validate`.
+    fn _validate_configs(configs: &GameConfigs) -> Result<(), GameError>
+    where
+        Self: Sized,
+    {
+        configs.validate()
+    }
+
+    /// Validates the amount paid along with a commitment.
+    ///
+    /// By default the amount must be at least `min_round_contribution` (if set).
+    fn _validate_contribution(&self, value: Balance) -> Result<(), GameError> {
+        if let Some(min_round_contribution) = self.data::<Data>().configs.min_round_contribution {
+            ensure!(
+                value >= min_round_contribution,
+                GameError::InvalidRoundContribution
+            );
+        }
+        Ok(())
+    }
+
+    /// Called once the game has moved to `OnGoing` and the first round is set up.
+    fn _on_game_started(&mut self) -> Result<(), GameError> {
+        Ok(())
+    }
+
+    /// Called with a reveal which matched its commitment, before the reveal is recorded.
+    ///
+    /// Used to validate the revealed choice and apply any game specific side effects.
+    fn _on_reveal(&mut self, _player: AccountId, _reveal: (u128, u128)) -> Result<(), GameError> {
+        Ok(())
+    }
+
+    /// Computes the payouts of a round which has just been marked as `Ended`.
+    ///
+    /// The returned amounts are credited to each account by the caller, the protocol fee is
+    /// only taken from the winnings (see `RoundPayouts`).
+    fn _settle_round(&mut self, round: &GameRound) -> Result<RoundPayouts, GameError>;
+
+    /// Whether the game is over after `last_round` has been settled.
+    ///
+    /// By default the game ends once `max_rounds` rounds have been played.
+    fn _is_game_over(&self, _last_round: &GameRound) -> bool {
+        let data = self.data::<Data>();
+        data.configs
+            .max_rounds
+            .map_or(false, |max_rounds| max_rounds < data.next_round_id.into())
+    }
+
+    fn _emit_player_joined_event(&self, _player: AccountId) {}
+
+    fn _emit_player_left_event(&self, _player: AccountId) {}
+
+    fn _emit_game_cancelled_event(&self) {}
+
+    fn _emit_game_started_event(&self) {}
+
+    fn _emit_player_committed_event(&self, _player: AccountId, _commitment: Hash) {}
+
+    fn _emit_all_players_committed_event(&self, _round: &GameRound) {}
+
+    fn _emit_player_revealed_event(&self, _player: AccountId, _reveal: (u128, u128)) {}
+
+    fn _emit_round_completed_event(&self, _round: &GameRound, _payouts: &[(AccountId, u128)]) {}
+
+    fn _emit_round_force_closed_event(&self, _round_id: u8, _closed_by: AccountId) {}
+
+    fn _emit_player_penalized_event(&self, _round_id: u8, _player: AccountId, _penalty: u128) {}
+
+    fn _emit_game_ended_event(&self) {}
+
+    fn _emit_claimed_event(&self, _account: AccountId, _amount: u128) {}
+
+    fn _emit_game_settled_event(&self, _transfers: &[(AccountId, u128)]) {}
+
+    /// Called when `router` failed to handle one of the `RouterCallbacks`, identified by the
+    /// selector of the `callback`.
+    fn _emit_router_notification_failed_event(&self, _router: AccountId, _callback: [u8; 4]) {}
+}
+
+/// Helpers used by the default `Lifecycle` implementation, also available to the `Admin`
+/// implementation of each game so that admin actions follow the same rules as players.
+pub trait LifecycleInternal {
+    /// Validates the configurations and initializes the game state, recording the creator of
+    /// the game (see `GameConfigs::creator`) and the value paid as their endowment.
+    fn _init_game(&mut self, configs: GameConfigs) -> Result<(), GameError>;
+
+    /// Adds a player to the game.
+    ///
+    /// Returns the number of players.
+    fn _add_player(&mut self, player: AccountId) -> Result<u8, GameError>;
+
+    /// Credits the join fees paid by `player` (or by every player if `None`) back to them.
+    fn _refund_join_fees(&mut self, player: Option<AccountId>);
+
+    /// Records a commitment, along with the contributed value, for a player in the current round.
+    fn _commit(
+        &mut self,
+        player: AccountId,
+        commitment: Hash,
+        value: Balance,
+    ) -> Result<(), GameError>;
+
+    /// Verifies a reveal against the player's commitment and records it in the current round.
+    fn _reveal(&mut self, player: AccountId, reveal: (u128, u128)) -> Result<(), GameError>;
+
+    /// Applies the configured `NonRevealPolicy` to the players of `round` who committed but
+    /// didn't reveal, recording their penalty in the round.
+    ///
+    /// The contributions which are no longer part of the pot are taken out of the round's
+    /// `total_contribution` and returned as payouts (refunds or shares for other players).
+    fn _apply_non_reveal_policy(&mut self, round: &mut GameRound) -> Vec<(AccountId, u128)>;
+
+    /// Settles the current round, credits the payouts and prepares the next round
+    /// (or ends the game).
+    fn _close_round(&mut self) -> Result<(), GameError>;
+
+    /// Closes the current round with whichever reveals were made so far.
+    ///
+    /// Emits the round force closed event with the caller as the account closing the round.
+    fn _force_close_round(&mut self) -> Result<(), GameError>;
+
+    /// Credits the join fees back to the players and the unused endowments back to their
+    /// funders, to be claimed like any other balance (see `Escrow::claim`).
+    ///
+    /// Penalties are not taken from the join fees, they were already taken from the round
+    /// contributions (see `_apply_non_reveal_policy`).
+    ///
+    /// Returns every refund credited, nothing is refunded twice.
+    fn _settle_game(&mut self) -> Vec<(AccountId, Balance)>;
+
+    /// Settles the game and, once every balance has been claimed, terminates the contract,
+    /// sending the remaining balance (forfeited contributions and penalties) to the creator.
+    ///
+    /// While some balances haven't been claimed, the game is only settled. Fails with
+    /// `BalancesNotSettled` if there was nothing left to settle.
+    fn _terminate_game(&mut self) -> Result<(), GameError>;
+
+    /// Takes the protocol fee out of the `payouts` of a round and sends it to the router.
+    ///
+    /// Returns the fee collected, nothing is taken if the transfer to the router fails.
+    fn _collect_protocol_fee(&mut self, payouts: &mut Vec<(AccountId, u128)>) -> u128;
+
+    /// Notifies the router (if any) that the game has started, see `RouterCallbacks`.
+    fn _notify_game_started(&self);
+
+    /// Notifies the router (if any) that a round has been completed, along with the fee
+    /// it collected.
+    fn _notify_round_completed(&self, round_id: u8, fee: u128);
+
+    /// Notifies the router (if any) that the game has ended or has been cancelled.
+    fn _notify_game_ended(&self);
+}
+
+/// Calls one of the `RouterCallbacks` messages on `router`, returns whether the call succeeded.
+///
+/// A faulty router must never block the game, so failures are only reported with
+/// `Hooks::_emit_router_notification_failed_event`.
+fn notify_router<Args: scale::Encode>(router: AccountId, input: ExecutionInput<Args>) -> bool {
+    matches!(
+        build_call::<DefaultEnvironment>()
+            .call_type(Call::new().callee(router).gas_limit(0))
+            .exec_input(input)
+            .returns::<Result<(), GameError>>()
+            .try_invoke(),
+        Ok(Ok(Ok(())))
+    )
+}
+
+impl<T: Storage<Data> + Hooks> LifecycleInternal for T {
+    default fn _init_game(&mut self, configs: GameConfigs) -> Result<(), GameError> {
+        T::_validate_configs(&configs)?;
+
+        let data = self.data::<Data>();
+        data.created_by = configs.creator.unwrap_or_else(|| Self::env().caller());
+        // the value paid to instantiate the game is the creator's endowment
+        let endowment = Self::env().transferred_value();
+        if endowment > 0 {
+            data.endowments.push((data.created_by, endowment));
+        }
+        data.configs = configs;
+
+        Ok(())
+    }
+
+    default fn _add_player(&mut self, player: AccountId) -> Result<u8, GameError> {
+        let data = self.data::<Data>();
+        // ensure players only join a game which hasn't started
+        ensure!(
+            data.status == GameStatus::Ready,
+            GameError::InvalidGameState
+        );
+        // ensure that there's more room in the game
+        ensure!(
+            data.players.len() < data.configs.max_players as usize,
+            GameError::MaxPlayersReached
+        );
+        // ensure the player isn't already in the game
+        ensure!(
+            !data.players.contains(&player),
+            GameError::PlayerAlreadyJoined
+        );
+        // ensure the game still accepts players
+        if let Some(join_deadline) = data.configs.join_deadline {
+            ensure!(
+                Self::env().block_number() <= join_deadline,
+                GameError::JoinDeadlinePassed
+            );
+        }
+
+        data.players.push(player);
+        let players_count = data.players.len() as u8;
+
+        self._emit_player_joined_event(player);
+
+        Ok(players_count)
+    }
+
+    default fn _refund_join_fees(&mut self, player: Option<AccountId>) {
+        let join_fees = self.data::<Data>().join_fees.clone();
+        let (refunded, kept): (Vec<_>, Vec<_>) = join_fees
+            .into_iter()
+            .partition(|(p, _)| player.map_or(true, |player| p == &player));
+
+        for (p, fee) in refunded {
+            self._credit(p, fee);
+        }
+        self.data::<Data>().join_fees = kept;
+    }
+
+    default fn _commit(
+        &mut self,
+        player: AccountId,
+        commitment: Hash,
+        value: Balance,
+    ) -> Result<(), GameError> {
+        // ensure valid game state
+        ensure!(
+            self.data::<Data>().status == GameStatus::OnGoing,
+            GameError::GameNotStarted
+        );
+        // ensure current round exists
+        ensure!(
+            self.data::<Data>().current_round.is_some(),
+            GameError::NoCurrentRound
+        );
+        // ensure the player is part of this game
+        ensure!(
+            self.data::<Data>().players.contains(&player),
+            GameError::PlayerNotInGame
+        );
+
+        self._validate_contribution(value)?;
+
+        let block = Self::env().block_number();
+        let data = self.data::<Data>();
+        let players_count = data.players.len();
+        let current_round = data.current_round.as_mut().unwrap();
+
+        // ensure the commit phase is still open
+        ensure!(
+            current_round.is_commit_open(block),
+            GameError::CommitDeadlinePassed
+        );
+        // ensure that the player hasn't already made a commitment
+        ensure!(
+            !current_round
+                .player_commits
+                .iter()
+                .any(|(p, _)| p == &player),
+            GameError::PlayerAlreadyCommitted
+        );
+        // ensure the commitment wasn't copied from another player
+        ensure!(
+            !current_round
+                .player_commits
+                .iter()
+                .any(|(_, c)| c == &commitment),
+            GameError::DuplicateCommitment
+        );
+
+        if current_round.status == RoundStatus::Ready {
+            current_round.status = RoundStatus::OnGoing;
+        }
+
+        // store the commit and keep track of round contribution(s)
+        current_round.player_commits.push((player, commitment));
+        current_round.player_contributions.push((player, value));
+        current_round.total_contribution += value;
+
+        let all_committed = current_round.player_commits.len() == players_count;
+        let round = current_round.clone();
+
+        self._emit_player_committed_event(player, commitment);
+
+        // check if all players have committed
+        if all_committed {
+            self._emit_all_players_committed_event(&round);
+        }
+
+        Ok(())
+    }
+
+    default fn _reveal(
+        &mut self,
+        player: AccountId,
+        reveal: (u128, u128),
+    ) -> Result<(), GameError> {
+        // ensure valid game state
+        ensure!(
+            self.data::<Data>().status == GameStatus::OnGoing,
+            GameError::GameNotStarted
+        );
+        // ensure current round exists
+        ensure!(
+            self.data::<Data>().current_round.is_some(),
+            GameError::NoCurrentRound
+        );
+        // ensure the round wasn't closed, the non-revealers were already dealt with
+        ensure!(
+            self.data::<Data>().current_round.as_ref().unwrap().status != RoundStatus::Ended,
+            GameError::InvalidRoundState
+        );
+
+        let block = Self::env().block_number();
+        let game = Self::env().account_id();
+        let current_round = self.data::<Data>().current_round.as_ref().unwrap();
+        let expected_commitment = hash_commitment(&game, current_round.id, &player, reveal);
+
+        // ensure the reveal phase is still open
+        ensure!(
+            current_round.is_reveal_open(block),
+            GameError::RevealDeadlinePassed
+        );
+
+        let player_commitment = current_round
+            .player_commits
+            .iter()
+            .find(|(p, _)| p == &player);
+
+        // ensure that the commitment exists
+        ensure!(player_commitment.is_some(), GameError::CommitmentNotFound);
+        // ensure that the reveal is valid
+        ensure!(
+            player_commitment.unwrap().1 == expected_commitment,
+            GameError::InvalidReveal
+        );
+        // ensure that the player hasn't already revealed
+        ensure!(
+            !current_round
+                .player_reveals
+                .iter()
+                .any(|(p, _)| p == &player),
+            GameError::PlayerAlreadyRevealed
+        );
+
+        self._on_reveal(player, reveal)?;
+
+        // store the reveal
+        self.data::<Data>()
+            .current_round
+            .as_mut()
+            .unwrap()
+            .player_reveals
+            .push((player, reveal));
+
+        self._emit_player_revealed_event(player, reveal);
+
+        Ok(())
+    }
+
+    default fn _apply_non_reveal_policy(
+        &mut self,
+        round: &mut GameRound,
+    ) -> Vec<(AccountId, u128)> {
+        let non_revealers = round.non_revealers();
+        if non_revealers.is_empty() {
+            return Vec::new();
+        }
+
+        let mut policy = self.data::<Data>().configs.non_reveal_policy;
+        if policy == NonRevealPolicy::ForfeitToOthers && round.player_reveals.is_empty() {
+            policy = NonRevealPolicy::ForfeitToPot;
+        }
+
+        let mut payouts = Vec::new();
+        for (player, contribution) in non_revealers {
+            let penalty = policy.penalty(contribution);
+            let refund = contribution - penalty;
+            if refund > 0 {
+                payouts.push((player, refund));
+                round.total_contribution -= refund;
+            }
+            round.non_reveal_penalties.push((player, penalty));
+            let penalties = self.data::<Data>().penalties.get(&player).unwrap_or(0);
+            self.data::<Data>()
+                .penalties
+                .insert(&player, &(penalties + penalty));
+            self._emit_player_penalized_event(round.id, player, penalty);
+        }
+
+        match policy {
+            NonRevealPolicy::ForfeitToOthers => {
+                // split the forfeited contributions, any remainder stays in the pot
+                let forfeited: u128 = round.non_reveal_penalties.iter().map(|(_, p)| p).sum();
+                let share = forfeited / round.player_reveals.len() as u128;
+                if share > 0 {
+                    for (player, _) in round.player_reveals.iter() {
+                        payouts.push((*player, share));
+                    }
+                    round.total_contribution -= share * round.player_reveals.len() as u128;
+                }
+            }
+            NonRevealPolicy::Exclude => {
+                let data = self.data::<Data>();
+                data.players
+                    .retain(|player| !round.non_reveal_penalties.iter().any(|(p, _)| p == player));
+            }
+            _ => {}
+        }
+
+        payouts
+    }
+
+    default fn _close_round(&mut self) -> Result<(), GameError> {
+        let mut round = self.data::<Data>().current_round.clone().unwrap();
+
+        // mark round as ended
+        round.status = RoundStatus::Ended;
+
+        // deal with the players who didn't reveal, then credit the payouts
+        // to be claimed by each player
+        let mut payouts = self._apply_non_reveal_policy(&mut round);
+        let RoundPayouts {
+            refunds,
+            mut winnings,
+        } = self._settle_round(&round)?;
+        round.protocol_fee = self._collect_protocol_fee(&mut winnings);
+        payouts.extend(refunds);
+        payouts.extend(winnings);
+        for (player, amount) in payouts.iter() {
+            self._credit(*player, *amount);
+        }
+        round.total_reward = payouts.iter().map(|(_, amount)| amount).sum();
+
+        // archive the round
+        let data = self.data::<Data>();
+        data.rounds.insert(&u32::from(round.id), &round);
+        data.rounds_count += 1;
+        data.current_round = Some(round.clone());
+        self._emit_round_completed_event(&round, &payouts);
+        self._notify_round_completed(round.id, round.protocol_fee);
+
+        // check if there's a next round or game ended
+        if self._is_game_over(&round) {
+            self.data::<Data>().status = GameStatus::Ended;
+            self._emit_game_ended_event();
+            self._notify_game_ended();
+        } else {
+            let block = Self::env().block_number();
+            let data = self.data::<Data>();
+            data.current_round = Some(GameRound::new(data.next_round_id, block, &data.configs));
+            data.next_round_id += 1;
+        }
+
+        Ok(())
+    }
+
+    default fn _force_close_round(&mut self) -> Result<(), GameError> {
+        // ensure valid game state
+        ensure!(
+            self.data::<Data>().status == GameStatus::OnGoing,
+            GameError::GameNotStarted
+        );
+        // ensure current round exists
+        ensure!(
+            self.data::<Data>().current_round.is_some(),
+            GameError::NoCurrentRound
+        );
+
+        let current_round = self.data::<Data>().current_round.as_ref().unwrap();
+        let round_id = current_round.id;
+        // ensure round state is still valid
+        ensure!(
+            current_round.status != RoundStatus::Ended,
+            GameError::InvalidRoundState
+        );
+
+        self._emit_round_force_closed_event(round_id, Self::env().caller());
+
+        // close the round with whichever reveals exist
+        self._close_round()
+    }
+
+    default fn _settle_game(&mut self) -> Vec<(AccountId, Balance)> {
+        // refund the join fees, the penalties were already taken from the round contributions
+        let mut refunds = core::mem::take(&mut self.data::<Data>().join_fees);
+        for (player, fee) in refunds.iter() {
+            self._credit(*player, *fee);
+        }
+
+        // return whatever is left of the endowments to their funders, the balances credited
+        // so far are kept for their owners
+        let mut available = Self::env()
+            .balance()
+            .saturating_sub(Self::env().minimum_balance())
+            .saturating_sub(self.data::<Data>().total_claimable);
+        for (funder, endowment) in core::mem::take(&mut self.data::<Data>().endowments) {
+            let amount = endowment.min(available);
+            available -= amount;
+            self._credit(funder, amount);
+            refunds.push((funder, amount));
+        }
+
+        refunds.retain(|(_, amount)| *amount > 0);
+        if !refunds.is_empty() {
+            self._emit_game_settled_event(&refunds);
+        }
+
+        refunds
+    }
+
+    default fn _terminate_game(&mut self) -> Result<(), GameError> {
+        let refunds = self._settle_game();
+        // every balance, including the refunds, must be claimed before terminating
+        if !self._is_settled() {
+            ensure!(!refunds.is_empty(), GameError::BalancesNotSettled);
+            return Ok(());
+        }
+
+        // the remaining balance goes to the creator upon termination
+        let created_by = self.data::<Data>().created_by;
+        self._emit_game_settled_event(&[(created_by, Self::env().balance())]);
+
+        Self::env().terminate_contract(created_by);
+    }
+
+    default fn _collect_protocol_fee(&mut self, payouts: &mut Vec<(AccountId, u128)>) -> u128 {
+        let configs = &self.data::<Data>().configs;
+        let (router, fee_bps) = match configs.router {
+            Some(router) if configs.protocol_fee_bps > 0 => (router, configs.protocol_fee_bps),
+            _ => return 0,
+        };
+
+        let fees: Vec<u128> = payouts
+            .iter()
+            .map(|(_, amount)| amount.saturating_mul(u128::from(fee_bps)) / u128::from(MAX_FEE_BPS))
+            .collect();
+        let total_fee: u128 = fees.iter().sum();
+        // a failed transfer must not block the game, the players keep the fee in that case
+        if total_fee == 0 || Self::env().transfer(router, total_fee).is_err() {
+            return 0;
+        }
+
+        for ((_, amount), fee) in payouts.iter_mut().zip(fees) {
+            *amount -= fee;
+        }
+
+        total_fee
+    }
+
+    default fn _notify_game_started(&self) {
+        let data = self.data::<Data>();
+        if let Some(router) = data.configs.router {
+            let callback = ink::selector_bytes!("RouterCallbacks::on_game_started");
+            if !notify_router(
+                router,
+                ExecutionInput::new(Selector::new(callback)).push_arg(data.players.clone()),
+            ) {
+                self._emit_router_notification_failed_event(router, callback);
+            }
+        }
+    }
+
+    default fn _notify_round_completed(&self, round_id: u8, fee: u128) {
+        if let Some(router) = self.data::<Data>().configs.router {
+            let callback = ink::selector_bytes!("RouterCallbacks::on_round_completed");
+            if !notify_router(
+                router,
+                ExecutionInput::new(Selector::new(callback))
+                    .push_arg(round_id)
+                    .push_arg(fee),
+            ) {
+                self._emit_router_notification_failed_event(router, callback);
+            }
+        }
+    }
+
+    default fn _notify_game_ended(&self) {
+        let data = self.data::<Data>();
+        if let Some(router) = data.configs.router {
+            let callback = ink::selector_bytes!("RouterCallbacks::on_game_ended");
+            if !notify_router(
+                router,
+                ExecutionInput::new(Selector::new(callback)).push_arg(data.status),
+            ) {
+                self._emit_router_notification_failed_event(router, callback);
+            }
+        }
+    }
+}
+
+impl<T: Storage<Data> + Hooks> Lifecycle for T {
+    default fn start_game(&mut self) -> Result<(), GameError> {
+        let block = Self::env().block_number();
+        let data = self.data::<Data>();
+
+        // ensure game status is valid for state change
+        ensure!(
+            data.status == GameStatus::Ready,
+            GameError::InvalidGameState
+        );
+        // ensure enough players
+        ensure!(
+            data.players.len() >= data.configs.min_players as usize,
+            GameError::NotEnoughPlayers
+        );
+
+        // setup the current round
+        data.current_round = Some(GameRound::new(data.next_round_id, block, &data.configs));
+        data.next_round_id += 1;
+        // update game state
+        data.status = GameStatus::OnGoing;
+
+        self._on_game_started()?;
+        self._emit_game_started_event();
+        self._notify_game_started();
+
+        Ok(())
+    }
+
+    default fn play_round(&mut self, commitment: Hash) -> Result<(), GameError> {
+        self._commit(
+            Self::env().caller(),
+            commitment,
+            Self::env().transferred_value(),
+        )
+    }
+
+    default fn reveal_round(&mut self, reveal: (u128, u128)) -> Result<(), GameError> {
+        self._reveal(Self::env().caller(), reveal)
+    }
+
+    default fn complete_round(&mut self) -> Result<(), GameError> {
+        let data = self.data::<Data>();
+        // ensure current round exists
+        ensure!(data.current_round.is_some(), GameError::NoCurrentRound);
+        let current_round = data.current_round.as_ref().unwrap();
+
+        // ensure all players have revealed
+        ensure!(
+            current_round.player_reveals.len() == data.players.len(),
+            GameError::NotAllPlayersRevealed
+        );
+        // ensure round state is still valid
+        ensure!(
+            current_round.status == RoundStatus::OnGoing,
+            GameError::InvalidRoundState
+        );
+
+        self._close_round()
+    }
+
+    default fn close_expired_round(&mut self) -> Result<(), GameError> {
+        if let Some(current_round) = self.data::<Data>().current_round.as_ref() {
+            // ensure the reveal deadline has passed
+            ensure!(
+                current_round.is_expired(Self::env().block_number()),
+                GameError::RoundNotExpired
+            );
+        }
+
+        self._force_close_round()
+    }
+
+    default fn cancel_game(&mut self) -> Result<(), GameError> {
+        let data = self.data::<Data>();
+        // ensure the game hasn't started
+        ensure!(
+            data.status == GameStatus::Ready,
+            GameError::InvalidGameState
+        );
+        // ensure the join deadline has passed
+        ensure!(
+            data.configs
+                .join_deadline
+                .map_or(false, |deadline| Self::env().block_number() > deadline),
+            GameError::JoinDeadlineNotPassed
+        );
+        // a game with enough players should be started instead
+        ensure!(
+            data.players.len() < data.configs.min_players as usize,
+            GameError::EnoughPlayersJoined
+        );
+
+        data.status = GameStatus::Cancelled;
+        self._refund_join_fees(None);
+
+        self._emit_game_cancelled_event();
+        self._notify_game_ended();
+
+        Ok(())
+    }
+
+    default fn end_game(&mut self) -> Result<(), GameError> {
+        // ensure the game is in ended (or cancelled) state
+        let status = self.data::<Data>().status;
+        ensure!(
+            status == GameStatus::Ended || status == GameStatus::Cancelled,
+            GameError::InvalidGameState
+        );
+        // refund the deposits and terminate the contract
+        self._terminate_game()
+    }
+}
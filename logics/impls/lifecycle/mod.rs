@@ -0,0 +1,3 @@
+pub mod lifecycle;
+
+pub use lifecycle::{Hooks, LifecycleInternal};
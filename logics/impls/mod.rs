@@ -1 +1,3 @@
-pub mod basic;
\ No newline at end of file
+pub mod basic;
+pub mod escrow;
+pub mod lifecycle;
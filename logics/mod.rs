@@ -1,3 +1,4 @@
-// pub mod impls;
+pub mod commitment;
 pub mod helpers;
+pub mod impls;
 pub mod traits;
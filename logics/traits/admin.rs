@@ -9,8 +9,9 @@ use openbrush::traits::AccountId;
 pub trait Admin {
     /// Adds a player into the game on their behalf.
     ///
-    /// Any amount paid into this method call is sent to the player being added to the game.
-    /// - This is optional and serves as an initial deposit to the players.
+    /// Any amount paid into this method call is held as the deposit of the player being added,
+    /// like a join fee, and refunded to them when the game ends or is cancelled.
+    /// - This is optional, e.g. the router forwards the stakes of the players it matched.
     ///
     /// This method must have access restriction such that only the contract deployer/admin
     /// can call it
@@ -26,11 +27,21 @@ pub trait Admin {
     ) -> Result<(), GameError>;
 
     /// Reveal a commitment on behalf of a player.
+    ///
+    /// The reveal must still match the commitment previously made by (or for) the player.
     #[ink(message)]
-    fn reveal_round_as_player(&mut self) -> Result<(), GameError>;
+    fn reveal_round_as_player(
+        &mut self,
+        as_player: AccountId,
+        reveal: (u128, u128),
+    ) -> Result<(), GameError>;
 
     /// Mark a round as completed and distribute rewards even if not all players have completed.
     ///
+    /// The round is closed using only the reveals made so far. Players who committed but never
+    /// revealed are penalized according to the game's `NonRevealPolicy`.
+    /// Emits a `RoundForceClosed` event.
+    ///
     /// This could optionally require an expiry (i.e. certain # of blocks passing).
     #[ink(message)]
     fn force_complete_round(&mut self) -> Result<(), GameError>;
@@ -1,6 +1,6 @@
 use super::types::*;
 use ink::prelude::vec::Vec;
-use openbrush::traits::AccountId;
+use openbrush::traits::{AccountId, Hash};
 
 /// Defines the basic game lifecycle methods.
 #[openbrush::trait_definition]
@@ -21,10 +21,41 @@ pub trait Basic {
     #[ink(message)]
     fn get_current_round(&self) -> Option<GameRound>;
 
+    /// Get a completed round by its id.
+    #[ink(message)]
+    fn get_round(&self, id: u32) -> Option<GameRound>;
+
+    /// Get up to `limit` completed rounds, starting with the round with id `from`.
+    #[ink(message)]
+    fn get_rounds(&self, from: u32, limit: u32) -> Vec<GameRound>;
+
+    /// Get the number of completed rounds.
+    #[ink(message)]
+    fn get_rounds_count(&self) -> u32;
+
+    /// Computes the commitment to `(input, nonce)` for `player` in the current round.
+    ///
+    /// The commitment binds this game's address, the round id and the player, see
+    /// `logics::commitment` for the exact scheme.
+    #[ink(message)]
+    fn hash_commitment(
+        &self,
+        player: AccountId,
+        input: u128,
+        nonce: u128,
+    ) -> Result<Hash, GameError>;
+
     /// Adds a player into the game by their AccountId.
     /// Ensures that caller of the function has the same AccountId being added (i.e. player can add themselves).
     ///
     /// Returns the number of players.
     #[ink(message, payable)]
     fn join(&mut self, player: AccountId) -> Result<u8, GameError>;
+
+    /// Removes the caller from a game which hasn't started yet.
+    /// Any join fee paid by the caller is credited back to them (see `Escrow::claim`).
+    ///
+    /// Returns the number of players left.
+    #[ink(message)]
+    fn leave(&mut self) -> Result<u8, GameError>;
 }
@@ -0,0 +1,20 @@
+use super::types::GameError;
+use openbrush::traits::{AccountId, Balance};
+
+/// Defines the pull-payment methods of a game.
+///
+/// Rewards and refunds are never pushed to the players while a round is being settled.
+/// Instead they are credited to a per-account ledger, and each account claims its balance.
+/// A failed claim only affects its caller, it never blocks the game.
+#[openbrush::trait_definition]
+pub trait Escrow {
+    /// Gets the balance which `account` can currently claim from the game.
+    #[ink(message)]
+    fn get_claimable(&self, account: AccountId) -> Balance;
+
+    /// Transfers the whole claimable balance of the caller to the caller.
+    ///
+    /// Returns the claimed amount, fails with `NothingToClaim` if the balance is empty.
+    #[ink(message)]
+    fn claim(&mut self) -> Result<Balance, GameError>;
+}
@@ -30,9 +30,28 @@ pub trait Lifecycle {
     #[ink(message, payable)]
     fn complete_round(&mut self) -> Result<(), GameError>;
 
+    /// closes the current round once its reveal deadline (see `GameConfigs::round_timeout`) has passed
+    /// callable by anyone, so a player who never reveals cannot hold the other players' stakes hostage
+    /// fails with `RoundNotExpired` if the deadline hasn't passed or the game has no round timeout
+    /// emits a relevant event
+    #[ink(message)]
+    fn close_expired_round(&mut self) -> Result<(), GameError>;
+
+    /// cancels a game which hasn't started once its join deadline (see `GameConfigs::join_deadline`) has passed
+    /// callable by anyone, so join fees are never stuck when not enough players join
+    /// fails with `JoinDeadlineNotPassed` if the deadline hasn't passed or the game has no join deadline
+    /// fails with `EnoughPlayersJoined` if `min_players` have joined, the game should be started instead
+    /// the join fees are credited back to the players (see `Escrow::claim`)
+    /// emits a relevant event
+    #[ink(message)]
+    fn cancel_game(&mut self) -> Result<(), GameError>;
+
     /// closes the game and terminates the contract
-    /// can only be done once all the rounds have been played
-    /// releases the joining fees (unless penalties are incurred)
+    /// can only be done once all the rounds have been played (or the game was cancelled)
+    /// credits the joining fees back to the players and the unused endowments
+    /// back to their funders, to be claimed (see `Escrow`)
+    /// the contract is terminated once every claimable balance has been claimed,
+    /// the remaining balance goes to the creator
     /// emits a relevant event
     #[ink(message, payable)]
     fn end_game(&mut self) -> Result<(), GameError>;
@@ -1,5 +1,9 @@
 pub mod admin;
 pub mod basic;
+pub mod escrow;
 pub mod lifecycle;
+pub mod ratings;
+pub mod router;
 pub mod types;
+pub mod upgradeable;
 pub mod utils;
@@ -0,0 +1,36 @@
+use super::types::GameError;
+use ink::prelude::vec::Vec;
+use openbrush::traits::AccountId;
+use scale::{Decode, Encode};
+
+/// The outcome of a round for one of its players.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Outcome {
+    Win,
+    Loss,
+    Tie,
+}
+
+/// The result of a round for one of its players: the move they revealed and its outcome.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct PlayerResult {
+    pub player: AccountId,
+    pub choice: u128,
+    pub outcome: Outcome,
+}
+
+/// Defines how games report the results of their rounds to a ratings contract, which keeps
+/// track of the players' ratings and stats across games.
+///
+/// A game only reports to the contract set in its `GameConfigs::ratings`. The reports are
+/// fire-and-forget: a failing report never blocks the game.
+#[openbrush::trait_definition]
+pub trait Ratings {
+    /// Records the results of a round, one per player who revealed.
+    ///
+    /// This method must have access restriction such that only trusted games can report.
+    #[ink(message)]
+    fn report_round(&mut self, results: Vec<PlayerResult>) -> Result<(), GameError>;
+}
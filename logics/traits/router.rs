@@ -0,0 +1,38 @@
+use super::types::{GameError, GameStatus};
+use ink::prelude::vec::Vec;
+use openbrush::traits::{AccountId, Balance};
+
+/// Defines the callbacks a game invokes on the router which instantiated it, so that the
+/// router can keep track of the status and players of its games.
+///
+/// A game only calls back the router set in its `GameConfigs::router`. A failing callback
+/// never blocks the game, the game reports it with an event instead.
+///
+/// Each callback fails with `GameNotRegistered` if the caller isn't a game of the router.
+#[openbrush::trait_definition]
+pub trait RouterCallbacks {
+    /// Called by a game once it has started, with the players of the game.
+    #[ink(message)]
+    fn on_game_started(&mut self, players: Vec<AccountId>) -> Result<(), GameError>;
+
+    /// Called by a game each time one of its rounds is completed, with the protocol fee
+    /// it transferred to the router for the round.
+    ///
+    /// Called again for the same round if more fees are collected from it later, e.g. when
+    /// its payouts are released after the round.
+    #[ink(message)]
+    fn on_round_completed(&mut self, round_id: u8, fee: Balance) -> Result<(), GameError>;
+
+    /// Called by a game once it has ended or has been cancelled.
+    #[ink(message)]
+    fn on_game_ended(&mut self, status: GameStatus) -> Result<(), GameError>;
+}
+
+/// Lets other contracts check whether a game was instantiated by the router, e.g. to trust
+/// the results the game reports.
+#[openbrush::trait_definition]
+pub trait GameRegistry {
+    /// Returns whether `game` is a game instantiated by the router.
+    #[ink(message)]
+    fn is_registered_game(&self, game: AccountId) -> bool;
+}
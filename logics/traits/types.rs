@@ -10,6 +10,8 @@ use scale::{Decode, Encode};
 pub enum GameError {
     /// Wrapper for events emitted from the access-control openbrush contract
     AccessControlError(AccessControlError),
+    /// The game configurations are not valid for this game
+    InvalidConfig(ConfigError),
     FailedToEmitEvent,
     FailedToGetWinners,
     /// Caller must match the palyer being added
@@ -20,6 +22,10 @@ pub enum GameError {
     InsufficientJoiningFees,
     /// The round has not expired yet
     RoundNotExpired,
+    /// The commit phase of the round has passed its deadline
+    CommitDeadlinePassed,
+    /// The reveal phase of the round has passed its deadline
+    RevealDeadlinePassed,
     /// No commitment made by player for the current round
     CommitmentNotFound,
     /// The commitment doesn't match the revealed value
@@ -46,14 +52,36 @@ pub enum GameError {
     NotAllPlayersRevealed,
     /// Failed to issue winner rewards
     FailedToIssueWinnerRewards,
+    /// The caller has no balance to claim
+    NothingToClaim,
+    /// Transfer of the claimed balance failed
+    ClaimFailed,
+    /// Some claimable balances haven't been claimed yet
+    BalancesNotSettled,
+    /// A refund transfer failed while settling the game
+    SettlementFailed,
     /// Player is already in the game
     PlayerAlreadyJoined,
+    /// Players can no longer join the game
+    JoinDeadlinePassed,
+    /// The game can't be cancelled before its join deadline
+    JoinDeadlineNotPassed,
+    /// The game has enough players to be started, so it can't be cancelled
+    EnoughPlayersJoined,
+    /// Player is not part of this game
+    PlayerNotInGame,
     /// Player already played n this round
     PlayerAlreadyCommitted,
+    /// The same commitment was already made by another player in this round
+    DuplicateCommitment,
+    /// Player already revealed their commitment in this round
+    PlayerAlreadyRevealed,
     /// Player choice for the round is not valid
     InvalidChoice,
     /// Admin error - failed to add player to game state
     FailedToAddPlayer,
+    /// The code of the game couldn't be replaced
+    FailedToSetCodeHash,
     /// Contract balance is not set for operation
     EndowmentNotSet,
     /// Contract balance is not enough for operation
@@ -62,6 +90,53 @@ pub enum GameError {
     SeedNotSet,
     /// Not enough balance to use in the game
     BalanceNotEnough,
+    /// The stage following the last round (see `GameConfigs::post_round_actions`) isn't completed
+    ActionStageOngoing,
+    /// There is no stage following a round to take part in
+    NoActionStage,
+    /// The caller isn't a game registered in the router
+    GameNotRegistered,
+}
+
+/// The reason why a set of `GameConfigs` was rejected.
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum ConfigError {
+    /// `max_players` must allow at least one player
+    NoPlayersAllowed,
+    /// `min_players` must not be greater than `max_players`
+    MinPlayersAboveMax,
+    /// The game needs both `min_round_contribution` and `max_round_contribution`
+    ContributionBoundsMissing,
+    /// `min_round_contribution` must not be greater than `max_round_contribution`
+    MinContributionAboveMax,
+    /// The game needs a `round_reward_multiplier`
+    RewardMultiplierMissing,
+    /// `round_timeout` must be at least one block
+    ZeroRoundTimeout,
+    /// `max_rounds` must allow at least one round
+    ZeroMaxRounds,
+    /// The penalty of `NonRevealPolicy::RefundMinusPenalty` can't exceed 100%
+    PenaltyAboveContribution,
+    /// The game doesn't support this number of players
+    UnsupportedPlayerCount,
+    /// The game doesn't support this number of rounds
+    UnsupportedRoundCount,
+    /// `protocol_fee_bps` can't exceed `MAX_FEE_BPS`
+    ProtocolFeeTooHigh,
+    /// The game doesn't support this number of moves
+    UnsupportedMoveCount,
+    /// The game doesn't support combining these modes, e.g. a series with elimination
+    IncompatibleModes,
+}
+
+/// The protocol fee is expressed in basis points, i.e. 10_000 is 100%.
+pub const MAX_FEE_BPS: u16 = 10_000;
+
+impl From<ConfigError> for GameError {
+    fn from(error: ConfigError) -> Self {
+        GameError::InvalidConfig(error)
+    }
 }
 
 impl From<AccessControlError> for GameError {
@@ -76,6 +151,8 @@ pub enum GameStatus {
     Ready,
     OnGoing,
     Ended,
+    /// The game was cancelled before it started
+    Cancelled,
 }
 
 #[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, Debug)]
@@ -96,10 +173,221 @@ pub struct GameRound {
     pub player_contributions: Vec<(AccountId, u128)>,
     pub total_contribution: u128,
     pub total_reward: u128,
+    /// The block at which the round was started.
+    pub start_block: u32,
+    /// The last block at which commitments are accepted (if the game has a `round_timeout`).
+    pub commit_deadline: Option<u32>,
+    /// The last block at which reveals are accepted (if the game has a `round_timeout`).
+    pub reveal_deadline: Option<u32>,
+    /// The penalty applied to each player who committed but never revealed,
+    /// see `GameConfigs::non_reveal_policy`.
+    pub non_reveal_penalties: Vec<(AccountId, u128)>,
+    /// The protocol fee taken from the round's payouts, see `GameConfigs::protocol_fee_bps`.
+    pub protocol_fee: u128,
+}
+
+impl GameRound {
+    /// Creates an empty round starting at `start_block`.
+    ///
+    /// When `round_timeout` is configured, the commit phase lasts `round_timeout` blocks and
+    /// is followed by a reveal phase of the same length.
+    pub fn new(id: u8, start_block: u32, configs: &GameConfigs) -> Self {
+        let commit_deadline = configs
+            .round_timeout
+            .map(|timeout| start_block.saturating_add(timeout));
+        let reveal_deadline = configs
+            .round_timeout
+            .map(|timeout| start_block.saturating_add(timeout.saturating_mul(2)));
+
+        Self {
+            id,
+            status: RoundStatus::Ready,
+            player_commits: Vec::new(),
+            player_reveals: Vec::new(),
+            player_contributions: Vec::new(),
+            total_contribution: 0,
+            total_reward: 0,
+            start_block,
+            commit_deadline,
+            reveal_deadline,
+            non_reveal_penalties: Vec::new(),
+            protocol_fee: 0,
+        }
+    }
+
+    /// Returns true if commitments are still accepted at the given block.
+    pub fn is_commit_open(&self, block: u32) -> bool {
+        self.commit_deadline
+            .map_or(true, |deadline| block <= deadline)
+    }
+
+    /// Returns true if reveals are still accepted at the given block.
+    pub fn is_reveal_open(&self, block: u32) -> bool {
+        self.reveal_deadline
+            .map_or(true, |deadline| block <= deadline)
+    }
+
+    /// Returns true once the reveal deadline has passed, i.e. anyone may close the round.
+    ///
+    /// Rounds without a deadline never expire.
+    pub fn is_expired(&self, block: u32) -> bool {
+        self.reveal_deadline
+            .map_or(false, |deadline| block > deadline)
+    }
+
+    /// Returns the players who committed in this round but never revealed, along with their
+    /// round contribution.
+    pub fn non_revealers(&self) -> Vec<(AccountId, u128)> {
+        self.player_contributions
+            .iter()
+            .filter(|(player, _)| !self.player_reveals.iter().any(|(p, _)| p == player))
+            .copied()
+            .collect()
+    }
+}
+
+/// The payouts of a round settled by a game, see `Hooks::_settle_round`.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct RoundPayouts {
+    /// What the players get back of their own contributions, no protocol fee is taken from it
+    pub refunds: Vec<(AccountId, u128)>,
+    /// What the players won in the round, the protocol fee is taken from it
+    pub winnings: Vec<(AccountId, u128)>,
+}
+
+impl RoundPayouts {
+    pub fn winnings(winnings: Vec<(AccountId, u128)>) -> Self {
+        Self {
+            refunds: Vec::new(),
+            winnings,
+        }
+    }
+
+    pub fn refunds(refunds: Vec<(AccountId, u128)>) -> Self {
+        Self {
+            refunds,
+            winnings: Vec::new(),
+        }
+    }
+}
+
+/// What happens to the contribution of a player who committed to a round but didn't reveal
+/// by the time the round is closed.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum NonRevealPolicy {
+    /// The whole contribution stays in the round's pot and is settled by the game.
+    #[default]
+    ForfeitToPot,
+    /// The whole contribution is split equally between the players who revealed.
+    /// Falls back to `ForfeitToPot` if nobody revealed.
+    ForfeitToOthers,
+    /// The contribution is refunded, minus a penalty (a percentage of the contribution)
+    /// which stays in the round's pot.
+    RefundMinusPenalty { penalty_percent: u8 },
+    /// Same as `ForfeitToPot`, and the player is removed from the game's future rounds.
+    Exclude,
+}
+
+/// What happens when a round ends in a tie.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum TiePolicy {
+    /// The round is replayed, its pot is carried over to the next round.
+    /// Falls back to `Refund` on the last round allowed by `max_rounds`.
+    #[default]
+    Replay,
+    /// The pot is split back between the tied players and the game ends.
+    Refund,
 }
 
+/// How the value produced by a round of a public good game grows with its pot, see `PayoffRule`.
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub enum PayoffFormula {
+    /// The pot is multiplied by the `round_reward_multiplier`.
+    #[default]
+    Linear,
+    /// Nothing is produced unless the pot reaches `threshold`, then it grows as `Linear`.
+    Threshold { threshold: u128 },
+    /// The multiplied pot has diminishing returns, producing half of what `Linear` would
+    /// when the pot reaches `half_saturation`, and never more than `Linear` would for
+    /// a pot of `half_saturation`.
+    Saturating { half_saturation: u128 },
+}
+
+/// The points a player assigns to another member of their group in the stage following a round,
+/// see `GameConfigs::post_round_actions`.
 #[derive(Encode, Decode, PartialEq, Eq, Clone, Debug)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct PointsAssignment {
+    pub to: AccountId,
+    pub punishment: u32,
+    pub reward: u32,
+}
+
+/// The price of each point assigned in the stage following a round, for the player assigning
+/// it (the cost) and for the player receiving it (the impact).
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Copy, Debug)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
+pub struct ActionRatios {
+    pub punishment_cost: u128,
+    /// Taken from the payout of the punished player
+    pub punishment_impact: u128,
+    pub reward_cost: u128,
+    /// Added to the payout of the rewarded player
+    pub reward_impact: u128,
+}
+
+/// Punishing costs a third of its impact, as in Fehr & Gächter (2000), and rewards are transfers.
+impl Default for ActionRatios {
+    fn default() -> Self {
+        Self {
+            punishment_cost: 1,
+            punishment_impact: 3,
+            reward_cost: 1,
+            reward_impact: 1,
+        }
+    }
+}
+
+impl ActionRatios {
+    /// Returns what `assignment` costs to the player assigning the points.
+    pub fn cost(&self, assignment: &PointsAssignment) -> u128 {
+        self.punishment_cost
+            .saturating_mul(u128::from(assignment.punishment))
+            .saturating_add(
+                self.reward_cost
+                    .saturating_mul(u128::from(assignment.reward)),
+            )
+    }
+
+    /// Returns what `assignment` takes from and adds to the payout of the player receiving
+    /// the points.
+    pub fn impact(&self, assignment: &PointsAssignment) -> (u128, u128) {
+        (
+            self.punishment_impact
+                .saturating_mul(u128::from(assignment.punishment)),
+            self.reward_impact
+                .saturating_mul(u128::from(assignment.reward)),
+        )
+    }
+}
+
+impl NonRevealPolicy {
+    /// Returns the part of `contribution` which the player loses under this policy.
+    pub fn penalty(&self, contribution: u128) -> u128 {
+        match self {
+            NonRevealPolicy::RefundMinusPenalty { penalty_percent } => {
+                contribution.saturating_mul(u128::from(*penalty_percent).min(100)) / 100
+            }
+            _ => contribution,
+        }
+    }
+}
+
+#[derive(Encode, Decode, PartialEq, Eq, Clone, Debug, Default)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, StorageLayout))]
 pub struct GameConfigs {
     pub max_players: u8,
     pub min_players: u8,
@@ -107,12 +395,79 @@ pub struct GameConfigs {
     pub max_round_contribution: Option<u128>,
     /// The multiplier for the round reward. Always divisible by 10 to allow for decimal values.
     pub round_reward_multiplier: Option<i8>,
+    /// Each round is followed by a stage in which players assign punishment and reward points
+    /// to each other, adjusting the payouts of the round before they are released.
+    /// Only applies to the games which support it.
     pub post_round_actions: bool,
     /// The number of blocks before a round is considered stale.
     pub round_timeout: Option<u32>,
     pub max_rounds: Option<u32>,
     pub join_fee: Option<u128>,
+    /// The last block at which players can join, once passed anyone can cancel the game
+    /// if it hasn't started.
+    pub join_deadline: Option<u32>,
     pub is_rounds_based: bool,
+    /// Applied to players who committed but didn't reveal when a round is closed.
+    pub non_reveal_policy: NonRevealPolicy,
+    /// Applied by the games which can end a round in a tie.
+    pub tie_policy: TiePolicy,
+    /// The losers of each round are removed from the game, until a single player remains.
+    /// Only applies to the games which have winners and losers in each round.
+    pub elimination: bool,
+    /// The number of moves a player can choose from, for the games with a configurable
+    /// move set. Each game has its own default.
+    pub move_count: Option<u8>,
+    /// The router to notify of the game's lifecycle, see `RouterCallbacks`.
+    /// Set by the router for the games it instantiates.
+    pub router: Option<AccountId>,
+    /// The account creating the game through the `router`, which shares the `CREATOR` role
+    /// with the router and is sent the remaining balance when the game is terminated.
+    /// Set by the router, the caller of the constructor is the creator otherwise.
+    pub creator: Option<AccountId>,
+    /// The contract to report the results of each round to, see `Ratings`.
+    pub ratings: Option<AccountId>,
+    /// The fee, in basis points, taken from the payouts of each round and sent to the `router`.
+    /// Set by the router from the fee of the game type, only applies if `router` is set.
+    pub protocol_fee_bps: u16,
+    /// How the pot of each round is turned into the players' payoffs, for the public good game.
+    pub payoff_formula: PayoffFormula,
+    /// The price of the points assigned after each round, see `post_round_actions`.
+    pub action_ratios: ActionRatios,
+}
+
+impl GameConfigs {
+    /// Checks the rules which apply to every game.
+    ///
+    /// Each game adds its own rules on top of these, see `Hooks::_validate_configs`.
+    pub fn validate(&self) -> Result<(), GameError> {
+        if self.max_players == 0 {
+            return Err(ConfigError::NoPlayersAllowed.into());
+        }
+        if self.min_players > self.max_players {
+            return Err(ConfigError::MinPlayersAboveMax.into());
+        }
+        if let (Some(min), Some(max)) = (self.min_round_contribution, self.max_round_contribution) {
+            if min > max {
+                return Err(ConfigError::MinContributionAboveMax.into());
+            }
+        }
+        if self.round_timeout == Some(0) {
+            return Err(ConfigError::ZeroRoundTimeout.into());
+        }
+        if self.max_rounds == Some(0) {
+            return Err(ConfigError::ZeroMaxRounds.into());
+        }
+        if let NonRevealPolicy::RefundMinusPenalty { penalty_percent } = self.non_reveal_policy {
+            if penalty_percent > 100 {
+                return Err(ConfigError::PenaltyAboveContribution.into());
+            }
+        }
+        if self.protocol_fee_bps > MAX_FEE_BPS {
+            return Err(ConfigError::ProtocolFeeTooHigh.into());
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
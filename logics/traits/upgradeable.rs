@@ -0,0 +1,17 @@
+use super::types::GameError;
+use openbrush::traits::Hash;
+
+/// Defines how a running game is migrated to a new version of its code.
+///
+/// The shared game state uses `openbrush::upgradeable_storage`, so a new version of a game
+/// keeps reading the same `Data` as long as the fields of `Data` are only ever added
+/// (using `_reserved`).
+#[openbrush::trait_definition]
+pub trait Upgradeable {
+    /// Replaces the code of this game with the code identified by `code_hash`.
+    ///
+    /// This method must have access restriction such that only the game's creator
+    /// (i.e. the router for games it instantiated) can call it.
+    #[ink(message)]
+    fn set_code_hash(&mut self, code_hash: Hash) -> Result<(), GameError>;
+}
@@ -10,3 +10,58 @@ pub trait Utils {
         players: &Vec<AccountId>,
     ) -> Result<Vec<(AccountId, Option<u128>)>, GameError>;
 }
+
+/// Defines how the contributions of a round are turned into the payoffs of a group of players,
+/// as in a public goods game: the pot produces a public good which benefits every member of the
+/// group, whether they contributed or not.
+pub trait PayoffRule {
+    /// Returns the value produced by a pot of `total`, with `multiplier` divided by 10 like
+    /// `GameConfigs::round_reward_multiplier`.
+    fn produce(&self, total: u128, multiplier: u128) -> u128;
+
+    /// Returns the payoff of each member of `group`: the value produced by the sum of the
+    /// `contributions`, split equally. With a linear rule, each member gets back
+    /// `multiplier / 10 / group size` (the MPCR) of every unit contributed.
+    ///
+    /// The remainder of the split stays in the contract.
+    fn payoffs(
+        &self,
+        contributions: &[u128],
+        group: &[AccountId],
+        multiplier: u128,
+    ) -> Vec<(AccountId, u128)> {
+        if group.is_empty() {
+            return Vec::new();
+        }
+
+        let total = contributions.iter().fold(0u128, |total, contribution| {
+            total.saturating_add(*contribution)
+        });
+        let share = self.produce(total, multiplier) / group.len() as u128;
+
+        group.iter().map(|member| (*member, share)).collect()
+    }
+}
+
+impl PayoffRule for PayoffFormula {
+    fn produce(&self, total: u128, multiplier: u128) -> u128 {
+        let linear = total.saturating_mul(multiplier) / 10;
+        match self {
+            PayoffFormula::Linear => linear,
+            PayoffFormula::Threshold { threshold } => {
+                if total < *threshold {
+                    0
+                } else {
+                    linear
+                }
+            }
+            PayoffFormula::Saturating { half_saturation } => {
+                match total.checked_add(*half_saturation) {
+                    Some(0) => 0,
+                    Some(sum) => linear.saturating_mul(*half_saturation) / sum,
+                    None => half_saturation.saturating_mul(multiplier) / 10,
+                }
+            }
+        }
+    }
+}